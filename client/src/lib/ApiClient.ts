@@ -1,4 +1,4 @@
-import type { AccessToken, LoggedInUserDetails, TrackPoints, UpdateUserRequest } from "./types";
+import type { AccessToken, LoggedInUserDetails, Performance, TrackPoints, UpdateUserRequest } from "./types";
 import { HttpStatusCode, baseAPIUrl } from "./const";
 
 export class APIClient {
@@ -24,10 +24,14 @@ export class APIClient {
         return undefined;
     }
 
-    async loadDataToDB(): Promise<any> {
+    async loadDataToDB(token: string): Promise<any> {
         try {
             const url = `${this.baseAPIUrl}/api/world_aths/read`;
-            const response = await fetch(url);
+            const response = await fetch(url, {
+                headers: {
+                    'Authorization': `Bearer ${token}`,
+                },
+            });
             if (response.ok) {
                 return await response.json();
             }
@@ -170,41 +174,62 @@ export class APIClient {
         return [HttpStatusCode.BadRequest, undefined];
     }
 
-    async requestUserPoints(
-        token: string,
-        user_id: number,
-        points_id: number,
-        method: 'POST' | 'DELETE' = 'POST' // Default to 'POST'
-    ): Promise<[HttpStatusCode, any]> {
+    // Records the mark the athlete entered on the day they competed, scored against the row's edition.
+    async recordPerformance(token: string, row: TrackPoints, mark: number | string, date: string): Promise<[HttpStatusCode, Performance | undefined]> {
         try {
-            const url = `${this.baseAPIUrl}/api/user/user_points/${user_id}/${points_id}`;
+            const url = `${this.baseAPIUrl}/api/user/performances`;
             const rawResponse = await fetch(url, {
-                method: method,
+                method: 'POST',
                 headers: {
                     'Accept': 'application/json',
                     'Content-Type': 'application/json',
                     'Authorization': `Bearer ${token}`,
-                }
+                },
+                body: JSON.stringify({
+                    category: row.Category,
+                    gender: row.Gender,
+                    event: row.Event,
+                    mark: mark,
+                    date: date,
+                    edition: row.Edition,
+                }),
             });
 
-            console.log(`${method} user points response: ${rawResponse.status}`);
-
             if (rawResponse.ok) {
-                const content = await rawResponse.json();
-                return [HttpStatusCode.Ok, content];
-            } else {
-                console.error(`Failed to ${method.toLowerCase()} points to the user:`, rawResponse.statusText);
+                const content: Performance = await rawResponse.json();
+                return [HttpStatusCode.Created, content];
             }
+            console.error("Failed to record performance:", rawResponse.statusText);
         } catch (error) {
-            console.error(`Error when ${method.toLowerCase()} points to the user:`, error);
+            console.error("Error recording performance:", error);
         }
-
         return [HttpStatusCode.BadRequest, undefined];
     }
 
-    async GetMyPoints(token: string, user_id: number): Promise<TrackPoints[]> {
+    async deletePerformance(token: string, performance_id: number): Promise<HttpStatusCode> {
+        try {
+            const url = `${this.baseAPIUrl}/api/user/performances/${performance_id}`;
+            const rawResponse = await fetch(url, {
+                method: 'DELETE',
+                headers: {
+                    'Accept': 'application/json',
+                    'Authorization': `Bearer ${token}`,
+                }
+            });
+
+            if (rawResponse.ok) {
+                return HttpStatusCode.Ok;
+            }
+            console.error("Failed to delete performance:", rawResponse.statusText);
+        } catch (error) {
+            console.error("Error deleting performance:", error);
+        }
+        return HttpStatusCode.BadRequest;
+    }
+
+    async GetMyPerformances(token: string): Promise<Performance[]> {
         try {
-            const url = `${this.baseAPIUrl}/api/user/user_points/${user_id}`;
+            const url = `${this.baseAPIUrl}/api/user/performances`;
             const result = await fetch(url, {
                 method: 'GET',
                 headers: {
@@ -215,12 +240,11 @@ export class APIClient {
 
             if (result.ok) {
                 const content = await result.json();
-                console.log(content.user_points);
-                return content.user_points;
+                return content.performances;
             }
-            console.error("Failed to get user points:", result.statusText);
+            console.error("Failed to get performances:", result.statusText);
         } catch (error) {
-            console.error("Error fetching points:", error);
+            console.error("Error fetching performances:", error);
         }
         return [];
     }
@@ -5,6 +5,18 @@ export interface TrackPoints{
     Gender: string;
     Mark: number;
     Points: number;
+    Edition: number;
+}
+
+export interface Performance {
+    id: number;
+    category: string;
+    gender: string;
+    event: string;
+    mark: number;
+    mark_display: string;
+    date: string | null;
+    points: number | null;
 }
 
 
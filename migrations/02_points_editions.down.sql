@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS points_edition_event_idx;
+ALTER TABLE points DROP COLUMN IF EXISTS edition;
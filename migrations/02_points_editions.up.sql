@@ -0,0 +1,4 @@
+-- Scoring tables are published in editions, existing rows belong to the 2022 tables.
+ALTER TABLE points ADD COLUMN IF NOT EXISTS edition INTEGER NOT NULL DEFAULT 2022;
+
+CREATE INDEX IF NOT EXISTS points_edition_event_idx ON points (edition, category, gender, event);
@@ -0,0 +1 @@
+DROP INDEX IF EXISTS points_natural_key_idx;
@@ -0,0 +1,18 @@
+-- Scoring table rows are identified by edition, gender, category, event and mark so imports can upsert.
+-- Existing duplicates collapse onto the row with the most points, as that is the score the mark earns.
+CREATE TEMPORARY TABLE points_duplicates AS
+SELECT id, FIRST_VALUE(id) OVER (
+    PARTITION BY edition, gender, category, event, mark ORDER BY points DESC NULLS LAST, id
+) AS keep_id
+FROM points;
+
+DELETE FROM points_duplicates WHERE id = keep_id;
+
+INSERT INTO user_points (user_id, point_id)
+SELECT up.user_id, d.keep_id FROM user_points up JOIN points_duplicates d ON d.id = up.point_id
+ON CONFLICT DO NOTHING;
+DELETE FROM user_points WHERE point_id IN (SELECT id FROM points_duplicates);
+DELETE FROM points WHERE id IN (SELECT id FROM points_duplicates);
+DROP TABLE points_duplicates;
+
+CREATE UNIQUE INDEX IF NOT EXISTS points_natural_key_idx ON points (edition, gender, category, event, mark);
@@ -0,0 +1,3 @@
+ALTER TABLE users DROP COLUMN IF EXISTS gender;
+ALTER TABLE users DROP COLUMN IF EXISTS date_of_birth;
+DROP TABLE IF EXISTS age_factors;
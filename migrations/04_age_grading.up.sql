@@ -0,0 +1,16 @@
+-- WMA age factor tables, one factor per edition, gender, event and age, used to age-grade masters marks.
+CREATE TABLE IF NOT EXISTS age_factors (
+    id SERIAL PRIMARY KEY,
+    edition INTEGER NOT NULL,
+    gender VARCHAR(10) NOT NULL,
+    event VARCHAR(20) NOT NULL,
+    age INTEGER NOT NULL,
+    factor FLOAT NOT NULL,
+    open_standard FLOAT NOT NULL
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS age_factors_natural_key_idx ON age_factors (edition, gender, event, age);
+
+-- Logged in users are age-graded from their profile.
+ALTER TABLE users ADD COLUMN IF NOT EXISTS date_of_birth DATE;
+ALTER TABLE users ADD COLUMN IF NOT EXISTS gender VARCHAR(10);
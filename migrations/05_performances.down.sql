@@ -0,0 +1,16 @@
+CREATE TABLE IF NOT EXISTS user_points (
+    user_id INTEGER REFERENCES users(id),
+    point_id INTEGER REFERENCES points(id),
+    PRIMARY KEY (user_id, point_id)
+);
+
+-- Performances can only be linked back when their scored mark is a row of the table they were scored against.
+INSERT INTO user_points (user_id, point_id)
+SELECT pf.user_id, p.id
+FROM performances pf
+JOIN points p ON
+    p.edition = pf.edition AND p.category = pf.category AND p.gender = pf.gender AND
+    p.event = pf.event AND p.mark = pf.scored_mark
+ON CONFLICT DO NOTHING;
+
+DROP TABLE IF EXISTS performances;
@@ -0,0 +1,46 @@
+-- Each user's actual results, scored against the points tables when they are written.
+CREATE TABLE IF NOT EXISTS performances (
+    id SERIAL PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    category VARCHAR(20) NOT NULL,
+    gender VARCHAR(10) NOT NULL,
+    event VARCHAR(20) NOT NULL,
+    mark FLOAT NOT NULL,
+    performance_date DATE,
+    competition VARCHAR(255),
+    venue VARCHAR(255),
+    round VARCHAR(50),
+    place INTEGER,
+    wind FLOAT,
+    timing VARCHAR(10) NOT NULL DEFAULT 'fat',
+    track VARCHAR(10) NOT NULL DEFAULT 'banked',
+    notes TEXT,
+    edition INTEGER,
+    scored_mark FLOAT,
+    points INTEGER,
+    lower_is_better BOOLEAN NOT NULL,
+    wind_legal BOOLEAN NOT NULL,
+    date_created TIMESTAMP NOT NULL DEFAULT NOW(),
+    date_modified TIMESTAMP DEFAULT NULL
+);
+
+CREATE INDEX IF NOT EXISTS performances_user_event_idx ON performances (user_id, event, performance_date);
+
+-- Linked scoring table rows become performances with the row's mark and points. The date and the
+-- rest of the result were never recorded.
+INSERT INTO performances (
+    user_id, category, gender, event, mark, edition, scored_mark, points, lower_is_better, wind_legal, notes
+)
+SELECT up.user_id, p.category, p.gender, p.event, p.mark, p.edition, p.mark, p.points,
+    (
+        SELECT (ARRAY_AGG(e.mark ORDER BY e.points DESC))[1] < (ARRAY_AGG(e.mark ORDER BY e.points ASC))[1]
+        FROM points e
+        WHERE e.edition = p.edition AND e.category = p.category AND e.gender = p.gender AND e.event = p.event
+    ),
+    NOT (p.category = 'Outdoor' AND p.event IN ('100m', '200m', '100mH', '110mH', 'LJ', 'TJ')),
+    'Imported from a linked scoring table row'
+FROM user_points up
+JOIN points p ON p.id = up.point_id
+WHERE p.mark IS NOT NULL;
+
+DROP TABLE IF EXISTS user_points;
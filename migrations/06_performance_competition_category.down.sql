@@ -0,0 +1 @@
+ALTER TABLE performances DROP COLUMN IF EXISTS competition_category;
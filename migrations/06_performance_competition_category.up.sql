@@ -0,0 +1,2 @@
+-- The World Athletics ranking category of the competition a performance was set in, OW, DF, GW or A to F.
+ALTER TABLE performances ADD COLUMN IF NOT EXISTS competition_category VARCHAR(2);
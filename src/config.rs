@@ -1,7 +1,25 @@
+use crate::scoring::seasons::{SeasonStart, Seasons};
+
 fn get_env_var(var_name: &str) -> String {
     std::env::var(var_name).unwrap_or_else(|_| panic!("{} must be set", var_name))
 }
 
+fn get_season_start(var_name: &str, default: SeasonStart) -> SeasonStart {
+    match std::env::var(var_name) {
+        Ok(value) => value.parse().unwrap_or_else(|e| panic!("{} is invalid: {}", var_name, e)),
+        Err(_) => default,
+    }
+}
+
+fn get_admin_emails(var_name: &str) -> Vec<String> {
+    std::env::var(var_name)
+        .unwrap_or_default()
+        .split(',')
+        .map(|email| email.trim().to_lowercase())
+        .filter(|email| !email.is_empty())
+        .collect()
+}
+
 #[derive(Debug, Clone)]
 pub struct Config {
     pub database_url: String,
@@ -13,6 +31,11 @@ pub struct Config {
     pub refresh_token_public_key: String,
     pub refresh_token_expires_in: String,
     pub refresh_token_max_age: i64,
+    /// When the indoor and outdoor seasons start, used for season bests.
+    pub seasons: Seasons,
+    /// The users allowed to load scoring tables and fit the scoring engine, set as a comma-separated
+    /// list of emails in `ADMIN_EMAILS`.
+    pub admin_emails: Vec<String>,
 }
 
 impl Config {
@@ -29,6 +52,11 @@ impl Config {
         let refresh_token_expires_in = get_env_var("REFRESH_TOKEN_EXPIRED_IN");
         let refresh_token_max_age = get_env_var("REFRESH_TOKEN_MAXAGE");
 
+        let seasons = Seasons {
+            outdoor: get_season_start("OUTDOOR_SEASON_START", SeasonStart::OUTDOOR),
+            indoor: get_season_start("INDOOR_SEASON_START", SeasonStart::INDOOR),
+        };
+
         Config {
             database_url,
             access_token_private_key,
@@ -39,6 +67,8 @@ impl Config {
             refresh_token_expires_in,
             access_token_max_age: access_token_max_age.parse::<i64>().unwrap(),
             refresh_token_max_age: refresh_token_max_age.parse::<i64>().unwrap(),
+            seasons,
+            admin_emails: get_admin_emails("ADMIN_EMAILS"),
         }
     }
 }
\ No newline at end of file
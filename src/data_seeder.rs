@@ -2,11 +2,48 @@ use pwhash::bcrypt;
 use sqlx::{Pool, Postgres};
 use crate::models::user::{CreateUserRequest, User};
 use crate::routes::database_functions;
-use crate::{routes::database_functions::users_db::{create_user, get_user_by_username}, routes::database_functions::athletics_db::read_into_db};
+use crate::{routes::database_functions::users_db::{create_user, get_user_by_username}, routes::database_functions::athletics_db::{canonicalize_event_names, get_editions, read_into_db}};
+use crate::models::iaaf_points::DEFAULT_EDITION;
+use crate::routes::utils::lookup_cache::LookupCache;
 
-pub async fn seed_database(pool : &Pool<Postgres>){
+/// Seeds the users and the points table, unless the default edition is already loaded. Clears the
+/// lookup cache afterwards, since lookups made while seeding may have cached marks that were not
+/// loaded yet.
+///
+/// Events stored under an alias by older imports are renamed first, whether or not the table is
+/// seeded, so lookups by canonical name find them.
+pub async fn seed_database(pool : &Pool<Postgres>, lookups: &LookupCache){
     seed_users(pool).await;
-    read_into_db(pool).await;
+    match canonicalize_event_names(pool).await {
+        Ok(0) => {}
+        Ok(renamed) => {
+            println!("Renamed {} points rows to their canonical event names", renamed);
+            lookups.invalidate();
+        }
+        Err(e) => println!("Renaming events failed: {}", e),
+    }
+    match get_editions(pool).await {
+        Ok(editions) if editions.iter().any(|e| e.edition == DEFAULT_EDITION) => {
+            println!("Points table already has edition {}", DEFAULT_EDITION);
+            return;
+        }
+        Ok(_) => {}
+        Err(e) => {
+            println!("Points table seeding failed: {}", e);
+            return;
+        }
+    }
+
+    let result = read_into_db(pool, DEFAULT_EDITION, |_| {}).await;
+    // Even a failed import may have written some batches.
+    lookups.invalidate();
+    match result {
+        Ok(report) => println!(
+            "Points table seeded: {} inserted, {} updated, {} skipped, {} invalid",
+            report.inserted, report.updated, report.skipped, report.invalid
+        ),
+        Err(e) => println!("Points table seeding failed: {}", e),
+    }
 }
 
 async fn seed_users(pool : &Pool<Postgres>){
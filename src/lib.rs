@@ -1,4 +1,5 @@
 pub mod routes;
 pub mod models;
 pub mod config;
+pub mod scoring;
 mod data_seeder;
\ No newline at end of file
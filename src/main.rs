@@ -1,4 +1,4 @@
-use crate::routes::routes::AppState;
+use crate::routes::{database_functions::athletics_db::edition_file_location, routes::AppState, utils::{data_file::read_points_file, lookup_cache::LookupCache}};
 use axum::http::{
     header::{AUTHORIZATION, CONTENT_TYPE},
     Method,
@@ -7,7 +7,16 @@ use models::token::TokenDetails;
 use moka::future::Cache;
 use sqlx::{Pool, Postgres};
 use uuid::Uuid;
-use std::{env, sync::Arc, thread, time::Duration};
+use models::iaaf_points::DEFAULT_EDITION;
+use scoring::engine::ScoringEngine;
+use std::{
+    env,
+    path::Path,
+    collections::HashSet,
+    sync::{Arc, Mutex, RwLock},
+    thread,
+    time::Duration,
+};
 use sysinfo::System;
 use tokio::sync::broadcast;
 use tower_http::cors::{Any, CorsLayer};
@@ -17,6 +26,7 @@ mod routes;
 mod db;
 mod models;
 mod data_seeder;
+mod scoring;
 
 #[tokio::main]
 async fn main() {
@@ -25,8 +35,21 @@ async fn main() {
     let server_address = env::var("SERVER_ADDRESS").unwrap_or("127.0.0.1:3000".to_owned());
     let connection_pool: Pool<Postgres> = db::connect_to_database().await;
 
-    // Seed the database
-    data_seeder::seed_database(&connection_pool).await;
+    // Fit the scoring engine straight from the data file so scoring works before seeding completes
+    let engine_file = edition_file_location(DEFAULT_EDITION);
+    let engine_rows = match read_points_file(Path::new(&engine_file)).await {
+        Ok(mut rows) => {
+            for row in &mut rows {
+                row.edition.get_or_insert(DEFAULT_EDITION);
+            }
+            rows
+        }
+        Err(e) => {
+            println!("Scoring engine could not be fitted from {}: {}", engine_file, e);
+            vec![]
+        }
+    };
+    let engine = ScoringEngine::fit(&engine_rows);
 
     let (tx, _) = broadcast::channel::<routes::routes::Snapshot>(1);
     let cache:Cache<Uuid, TokenDetails> = Cache::builder()
@@ -34,6 +57,10 @@ async fn main() {
         .time_to_live(Duration::from_secs(60 * 60 * 24))
         .time_to_idle(Duration::from_secs(60 * 60 * 24))
         .build();
+    let import_jobs = Cache::builder()
+        .max_capacity(100)
+        .time_to_live(Duration::from_secs(60 * 60 * 24))
+        .build();
     tracing_subscriber::fmt::init();
 
     let cors = CorsLayer::new()
@@ -53,8 +80,16 @@ async fn main() {
         env: config.clone(),
         tx: tx.clone(),
         cache: cache.clone(),
+        engine: RwLock::new(engine),
+        import_jobs,
+        running_imports: Mutex::new(HashSet::new()),
+        lookups: LookupCache::default(),
     });
 
+    // Seed the database
+    let seed_state = app_state.clone();
+    tokio::spawn(async move { data_seeder::seed_database(&seed_state.db, &seed_state.lookups).await });
+
     let app = routes::routes::create_router(app_state).layer(cors);
 
     tokio::task::spawn_blocking(move || {
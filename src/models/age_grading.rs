@@ -0,0 +1,74 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::scoring::{age_grading::AgeGrade, timing::TimingMethod, track::TrackType};
+
+use super::iaaf_points::{empty_string_as_none, number_or_time, MarkScore};
+
+/// The WMA age factor edition used when a request does not ask for one and the data file has none.
+pub const DEFAULT_AGE_FACTOR_EDITION: i32 = 2023;
+
+/// One row of a WMA age factor table: the factor for an age in an event.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize, sqlx::FromRow)]
+#[serde(rename_all = "PascalCase")]
+pub struct AgeFactor {
+    pub gender: String,
+    pub event: String,
+    pub age: i32,
+    pub factor: f64,
+    /// The open class standard for the event in seconds or metres, the same for every age.
+    #[serde(deserialize_with = "number_or_time")]
+    pub open_standard: f64,
+    #[serde(default)]
+    #[sqlx(default)]
+    pub edition: Option<i32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AgeGradeQueryParams {
+    /// Seconds, metres or a clock-style time such as `3:45.12`.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub mark: Option<String>,
+    /// The athlete's age on the day of the competition. Logged in users can leave it out to use the
+    /// date of birth on their profile.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub age: Option<i32>,
+    /// The competition date the age is worked out on for logged in users, today when not given.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub date: Option<NaiveDate>,
+    /// The scoring table edition for the open equivalent points, the latest loaded one when not given.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub edition: Option<i32>,
+    /// The age factor edition, the latest loaded one when not given.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub factors_edition: Option<i32>,
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub timing: Option<TimingMethod>,
+    /// The track an indoor mark was set on, a standard banked track when not given.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub track: Option<TrackType>,
+}
+
+/// An age-graded mark along with the points its open class equivalent scores.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AgeGradeResult {
+    pub factors_edition: i32,
+    pub gender: String,
+    /// The canonical name of the event, see [`crate::scoring::events`].
+    pub event: String,
+    /// The mark as graded, after converting hand times and indoor times on non-standard tracks.
+    pub mark: f64,
+    pub mark_display: String,
+    /// The mark in feet and inches for events measured in metres.
+    pub mark_imperial: Option<String>,
+    pub timing: TimingMethod,
+    pub timing_adjustment: f64,
+    pub track: TrackType,
+    pub track_factor: f64,
+    #[serde(flatten)]
+    pub grade: AgeGrade,
+    pub age_graded_mark_imperial: Option<String>,
+    /// The age-graded mark scored against the open scoring tables, `None` when the event has no
+    /// points table in the category.
+    pub open_equivalent: Option<MarkScore>,
+}
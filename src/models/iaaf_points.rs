@@ -3,6 +3,18 @@
 use std::{fmt, str::FromStr};
 use serde::{de, Deserialize, Deserializer, Serialize};
 
+use crate::models::import_job::ImportTable;
+use crate::scoring::{
+    marks::{format_imperial_mark, format_mark, parse_mark},
+    timing::TimingMethod,
+    track::TrackType,
+    wind::WindStatus,
+    world_rankings::CompetitionCategory,
+};
+
+/// The scoring table edition used when a request does not ask for one and the data file has none.
+pub const DEFAULT_EDITION: i32 = 2022;
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize, sqlx::FromRow)]
 #[serde(rename_all = "PascalCase")]
 pub struct PointsInsert {
@@ -11,11 +23,23 @@ pub struct PointsInsert {
     pub gender: String,
     pub category: String,
     pub event: String,
+    #[serde(deserialize_with = "number_or_time")]
     pub mark: f64,
+    #[serde(default)]
+    #[sqlx(default)]
+    pub edition: Option<i32>,
 }
 
 impl PointsInsert {
-    pub fn new(id: Option<i32>, points: i32, gender: String, category: String, event: String, mark: f64) -> Self {
+    pub fn new(
+        id: Option<i32>,
+        points: i32,
+        gender: String,
+        category: String,
+        event: String,
+        mark: f64,
+        edition: Option<i32>,
+    ) -> Self {
         Self {
             id,
             points,
@@ -23,6 +47,7 @@ impl PointsInsert {
             category,
             event,
             mark,
+            edition,
         }
     }
 }
@@ -33,15 +58,482 @@ impl fmt::Display for PointsInsert {
     }
 }
 
+/// A mark to score against an event's points table. Names are matched ignoring case, the same as in
+/// CSV files and query strings.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MarkQuery {
+    #[serde(deserialize_with = "from_str")]
+    pub category: Category,
+    #[serde(deserialize_with = "from_str")]
+    pub gender: Gender,
+    pub event: String,
+    #[serde(deserialize_with = "number_or_time")]
+    pub mark: f64,
+    /// The scoring table edition, the latest one loaded for the event when `None`.
+    #[serde(default)]
+    pub edition: Option<i32>,
+    #[serde(default, deserialize_with = "from_str")]
+    pub timing: TimingMethod,
+    /// The wind reading in metres per second, for wind assisted events.
+    #[serde(default)]
+    pub wind: Option<f64>,
+    /// The track an indoor mark was set on.
+    #[serde(default, deserialize_with = "from_str")]
+    pub track: TrackType,
+}
+
+/// The outcome of scoring one entry of a bulk request: either a score or the reason it failed.
+#[derive(Debug, Serialize, Clone)]
+pub struct BulkScoreResult {
+    pub row: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entry: Option<MarkQuery>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub score: Option<MarkScore>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Raw row returned when scoring a mark: the table row the mark earns plus the row above it.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct MarkLookupRow {
+    pub idx: i32,
+    pub edition: i32,
+    pub event_name: String,
+    pub lower_is_better: bool,
+    pub rounded_mark: f64,
+    pub id: Option<i32>,
+    pub points: Option<i32>,
+    pub gender: Option<String>,
+    pub category: Option<String>,
+    pub event: Option<String>,
+    pub mark: Option<f64>,
+    pub next_points: Option<i32>,
+    pub next_mark: Option<f64>,
+}
+
+/// The score for a mark along with what is needed to reach the next points value.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MarkScore {
+    pub edition: i32,
+    /// The canonical name of the event, see [`crate::scoring::events`].
+    pub event: String,
+    /// The table row the mark earns, `None` if the mark is below the lowest table entry.
+    pub points: Option<PointsInsert>,
+    /// The mark after applying the World Athletics rounding rules.
+    pub mark: f64,
+    pub mark_display: String,
+    /// The mark in feet and inches for events measured in metres.
+    pub mark_imperial: Option<String>,
+    pub lower_is_better: bool,
+    pub timing: TimingMethod,
+    /// The seconds added to the submitted mark to convert a hand time, zero for automatic times.
+    pub timing_adjustment: f64,
+    pub track: TrackType,
+    /// The factor the mark was multiplied by to convert it to a standard banked track, 1.0 when no
+    /// conversion applies.
+    pub track_factor: f64,
+    #[serde(flatten)]
+    pub wind: WindStatus,
+    pub next_points: Option<i32>,
+    pub next_mark: Option<f64>,
+    pub next_mark_display: Option<String>,
+    pub next_mark_imperial: Option<String>,
+}
+
+impl From<MarkLookupRow> for MarkScore {
+    fn from(row: MarkLookupRow) -> Self {
+        let points = match (row.points, row.gender, row.category, row.event, row.mark) {
+            (Some(points), Some(gender), Some(category), Some(event), Some(mark)) => {
+                Some(PointsInsert::new(row.id, points, gender, category, event, mark, Some(row.edition)))
+            }
+            _ => None,
+        };
+
+        let imperial = |mark: f64| format_imperial_mark(mark, &row.event_name, row.lower_is_better);
+        Self {
+            edition: row.edition,
+            points,
+            mark: row.rounded_mark,
+            mark_display: format_mark(row.rounded_mark, row.lower_is_better),
+            mark_imperial: imperial(row.rounded_mark),
+            lower_is_better: row.lower_is_better,
+            timing: TimingMethod::Fat,
+            timing_adjustment: 0.0,
+            track: TrackType::Banked,
+            track_factor: 1.0,
+            wind: WindStatus::default(),
+            next_points: row.next_points,
+            next_mark: row.next_mark,
+            next_mark_display: row.next_mark.map(|mark| format_mark(mark, row.lower_is_better)),
+            next_mark_imperial: row.next_mark.and_then(imperial),
+            event: row.event_name,
+        }
+    }
+}
+
+/// Raw row returned when looking up the marks that earn a points value.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct MarkRangeRow {
+    pub edition: i32,
+    pub lower_is_better: bool,
+    pub id: Option<i32>,
+    pub points: i32,
+    pub gender: String,
+    pub category: String,
+    pub event: String,
+    pub mark: f64,
+    pub next_points: Option<i32>,
+    pub next_mark: Option<f64>,
+}
+
+/// The range of marks that earn a points value. When the requested points value cannot be achieved
+/// in the event the closest achievable one is used instead.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MarkRange {
+    pub edition: i32,
+    pub requested_points: i32,
+    pub exact: bool,
+    /// The table row for the achieved points value.
+    pub points: PointsInsert,
+    pub lower_is_better: bool,
+    /// The slowest time or shortest distance that earns the points.
+    pub worst_mark: f64,
+    pub worst_mark_display: String,
+    /// The worst mark in feet and inches for events measured in metres.
+    pub worst_mark_imperial: Option<String>,
+    /// The points value of the next table row up, `None` at the top of the table.
+    pub next_points: Option<i32>,
+    /// The mark of the next table row up. Marks from the worst mark up to but not including this one
+    /// earn the points; how close a mark can get depends on how the event is measured.
+    pub next_mark: Option<f64>,
+    pub next_mark_display: Option<String>,
+    pub next_mark_imperial: Option<String>,
+}
+
+impl MarkRange {
+    pub fn from_row(requested_points: i32, row: MarkRangeRow) -> Self {
+        let imperial = |mark: f64| format_imperial_mark(mark, &row.event, row.lower_is_better);
+        Self {
+            edition: row.edition,
+            requested_points,
+            exact: requested_points == row.points,
+            worst_mark: row.mark,
+            worst_mark_display: format_mark(row.mark, row.lower_is_better),
+            worst_mark_imperial: imperial(row.mark),
+            next_points: row.next_points,
+            next_mark: row.next_mark,
+            next_mark_display: row.next_mark.map(|mark| format_mark(mark, row.lower_is_better)),
+            next_mark_imperial: row.next_mark.and_then(imperial),
+            lower_is_better: row.lower_is_better,
+            points: PointsInsert::new(row.id, row.points, row.gender, row.category, row.event, row.mark, Some(row.edition)),
+        }
+    }
+}
+
+/// What a mark in an event measures.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum MarkType {
+    Time,
+    Distance,
+    Points,
+}
+
+impl MarkType {
+    /// Combined events are scored in points, every other event is either timed (lower is better) or
+    /// measured in metres.
+    pub fn for_event(event: &str, lower_is_better: bool) -> Self {
+        if event.to_lowercase().ends_with("athlon") {
+            MarkType::Points
+        } else if lower_is_better {
+            MarkType::Time
+        } else {
+            MarkType::Distance
+        }
+    }
+}
+
+/// Raw row with the aggregated points table data for one event.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct EventSummaryRow {
+    pub edition: i32,
+    pub category: String,
+    pub gender: String,
+    pub event: String,
+    pub lower_is_better: bool,
+    pub min_mark: f64,
+    pub max_mark: f64,
+    pub min_points: i32,
+    pub max_points: i32,
+}
+
+/// An event in the scoring tables along with the range of marks and points it covers.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EventSummary {
+    pub edition: i32,
+    pub category: String,
+    pub gender: String,
+    pub event: String,
+    pub mark_type: MarkType,
+    pub timed: bool,
+    pub lower_is_better: bool,
+    pub min_mark: f64,
+    pub min_mark_display: String,
+    pub min_mark_imperial: Option<String>,
+    pub max_mark: f64,
+    pub max_mark_display: String,
+    pub max_mark_imperial: Option<String>,
+    pub min_points: i32,
+    pub max_points: i32,
+}
+
+impl From<EventSummaryRow> for EventSummary {
+    fn from(row: EventSummaryRow) -> Self {
+        let mark_type = MarkType::for_event(&row.event, row.lower_is_better);
+        Self {
+            edition: row.edition,
+            mark_type,
+            timed: mark_type == MarkType::Time,
+            lower_is_better: row.lower_is_better,
+            min_mark: row.min_mark,
+            min_mark_display: format_mark(row.min_mark, row.lower_is_better),
+            min_mark_imperial: format_imperial_mark(row.min_mark, &row.event, row.lower_is_better),
+            max_mark: row.max_mark,
+            max_mark_display: format_mark(row.max_mark, row.lower_is_better),
+            max_mark_imperial: format_imperial_mark(row.max_mark, &row.event, row.lower_is_better),
+            min_points: row.min_points,
+            max_points: row.max_points,
+            category: row.category,
+            gender: row.gender,
+            event: row.event,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EventCatalogQueryParams {
+    pub category: Option<Category>,
+    pub gender: Option<Gender>,
+    pub edition: Option<i32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ImportQueryParams {
+    /// The edition to import, read from `data/WorldAthletics_<edition>.json`, or
+    /// `data/WmaAgeFactors_<edition>.json` for age factors.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub edition: Option<i32>,
+    /// The table to import into, the scoring tables when not given.
+    #[serde(default)]
+    pub table: ImportTable,
+}
+
+/// A scoring table edition loaded into the points table.
+#[derive(Debug, Serialize, Deserialize, Clone, sqlx::FromRow)]
+pub struct EditionSummary {
+    pub edition: i32,
+    pub events: i64,
+    pub rows: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EditionDiffQueryParams {
+    pub from: i32,
+    pub to: i32,
+    /// Serve the report as a JSON file download.
+    #[serde(default)]
+    pub download: bool,
+}
+
+/// The mark closest to a representative points value in one event of an edition.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct RepresentativeMark {
+    pub category: String,
+    pub gender: String,
+    pub event: String,
+    pub points: i32,
+    pub mark: f64,
+}
+
+/// The mark for one discipline of a combined event.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DisciplineMark {
+    pub event: String,
+    #[serde(deserialize_with = "number_or_time")]
+    pub mark: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CombinedEventsRequest {
+    pub marks: Vec<DisciplineMark>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct PointsSearchQueryParams {
     #[serde(default, deserialize_with = "empty_string_as_none")]
     pub points: Option<i32>,
+    /// Seconds, metres or a clock-style time such as `3:45.12`, parsed with [`parse_mark`].
     #[serde(default, deserialize_with = "empty_string_as_none")]
-    pub mark: Option<f64>,
+    pub mark: Option<String>,
+    /// The scoring table edition, the latest one loaded for the event when not given.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub edition: Option<i32>,
+    /// How the mark was timed, fully automatic when not given.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub timing: Option<TimingMethod>,
+    /// The wind reading in metres per second, for wind assisted events.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub wind: Option<f64>,
+    /// The track an indoor mark was set on, a standard banked track when not given.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub track: Option<TrackType>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EquivalentsQueryParams {
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub mark: Option<String>,
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub edition: Option<i32>,
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub timing: Option<TimingMethod>,
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub track: Option<TrackType>,
+    /// A comma separated list of events to compare against, every other event when not given.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub events: Option<String>,
 }
 
-fn empty_string_as_none<'de, D, T>(de: D) -> Result<Option<T>, D::Error>
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RankingScoreQueryParams {
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub mark: Option<String>,
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub edition: Option<i32>,
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub timing: Option<TimingMethod>,
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub wind: Option<f64>,
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub track: Option<TrackType>,
+    /// The World Athletics ranking category of the competition, no placing score when not given.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub competition_category: Option<CompetitionCategory>,
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub place: Option<i32>,
+    /// The round of the competition, a final when not given.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub round: Option<String>,
+}
+
+/// The formats a scoring curve can be exported in.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum CurveFormat {
+    #[default]
+    Json,
+    Csv,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CurveQueryParams {
+    /// The scoring table edition, the latest one loaded for the event when not given.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub edition: Option<i32>,
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub min_points: Option<i32>,
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub max_points: Option<i32>,
+    /// The lowest mark to include, parsed with [`parse_mark`].
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub min_mark: Option<String>,
+    /// The highest mark to include, parsed with [`parse_mark`].
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub max_mark: Option<String>,
+    /// Keep every `step`th table row, every row when not given.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub step: Option<i32>,
+    #[serde(default)]
+    pub format: CurveFormat,
+    /// Serve the curve as a file download.
+    #[serde(default)]
+    pub download: bool,
+}
+
+/// The part of an event's points table to read for a scoring curve.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CurveSlice {
+    /// The scoring table edition, the latest one loaded for the event when `None`.
+    pub edition: Option<i32>,
+    pub min_points: Option<i32>,
+    pub max_points: Option<i32>,
+    pub min_mark: Option<f64>,
+    pub max_mark: Option<f64>,
+    /// Keep every `step`th row of the slice.
+    pub step: i32,
+}
+
+/// Raw row returned when reading a slice of an event's points table.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct CurvePointRow {
+    pub edition: i32,
+    pub event: String,
+    pub lower_is_better: bool,
+    pub points: i32,
+    pub mark: f64,
+}
+
+/// One point of a scoring curve.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CurvePoint {
+    pub points: i32,
+    pub mark: f64,
+    pub mark_display: String,
+    pub mark_imperial: Option<String>,
+}
+
+/// The `(mark, points)` series of an event's points table, ordered by points.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ScoringCurve {
+    pub edition: i32,
+    pub category: String,
+    pub gender: String,
+    pub event: String,
+    pub mark_type: MarkType,
+    pub lower_is_better: bool,
+    pub step: i32,
+    pub series: Vec<CurvePoint>,
+}
+
+impl ScoringCurve {
+    /// Builds a curve from its table rows, `None` when there are none.
+    pub fn from_rows(category: &Category, gender: &Gender, step: i32, rows: Vec<CurvePointRow>) -> Option<Self> {
+        let first = rows.first()?;
+        let (edition, event, lower_is_better) = (first.edition, first.event.clone(), first.lower_is_better);
+        let series = rows
+            .into_iter()
+            .map(|row| CurvePoint {
+                points: row.points,
+                mark: row.mark,
+                mark_display: format_mark(row.mark, lower_is_better),
+                mark_imperial: format_imperial_mark(row.mark, &event, lower_is_better),
+            })
+            .collect();
+        Some(Self {
+            edition,
+            category: category.to_string(),
+            gender: gender.to_string(),
+            mark_type: MarkType::for_event(&event, lower_is_better),
+            event,
+            lower_is_better,
+            step,
+            series,
+        })
+    }
+}
+
+pub(crate) fn empty_string_as_none<'de, D, T>(de: D) -> Result<Option<T>, D::Error>
 where
     D: Deserializer<'de>,
     T: FromStr,
@@ -50,7 +542,37 @@ where
     let opt = Option::<String>::deserialize(de)?;
     match opt.as_deref() {
         None | Some("") => Ok(None),
-        Some(s) => FromStr::from_str(s).map_err(de::Error::custom).map(Some),
+        // A `+` in a query string arrives as a space, so `wind=+1.5` is read as " 1.5".
+        Some(s) => FromStr::from_str(s.trim()).map_err(de::Error::custom).map(Some),
+    }
+}
+
+/// Deserializes a string with the type's `FromStr` impl rather than its derived names.
+pub(crate) fn from_str<'de, D, T>(de: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    let s = String::deserialize(de)?;
+    T::from_str(&s).map_err(de::Error::custom)
+}
+
+/// Accepts a mark either as a number or as a clock-style time string.
+pub(crate) fn number_or_time<'de, D>(de: D) -> Result<f64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum RawMark {
+        Number(f64),
+        Text(String),
+    }
+
+    match RawMark::deserialize(de)? {
+        RawMark::Number(mark) => Ok(mark),
+        RawMark::Text(mark) => parse_mark(&mark).map_err(de::Error::custom),
     }
 }
 
@@ -64,6 +586,17 @@ impl fmt::Display for Gender {
         write!(f, "{:?}", self)
     }
 }
+impl FromStr for Gender {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "male" => Ok(Gender::Male),
+            "female" => Ok(Gender::Female),
+            _ => Err(format!("unknown gender '{}', expected Male or Female", s)),
+        }
+    }
+}
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub enum Category {
@@ -75,3 +608,14 @@ impl fmt::Display for Category {
         write!(f, "{:?}", self)
     }
 }
+impl FromStr for Category {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "indoor" => Ok(Category::Indoor),
+            "outdoor" => Ok(Category::Outdoor),
+            _ => Err(format!("unknown category '{}', expected Indoor or Outdoor", s)),
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// The number of validation errors kept in an import report.
+pub const MAX_REPORTED_ERRORS: usize = 100;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportStatus {
+    Running,
+    Completed,
+    Failed,
+}
+
+/// The table an import writes to.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportTable {
+    /// The World Athletics scoring tables.
+    #[default]
+    Points,
+    /// The WMA age factor tables used for age grading.
+    AgeFactors,
+}
+
+impl fmt::Display for ImportTable {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ImportTable::Points => write!(f, "points"),
+            ImportTable::AgeFactors => write!(f, "age factors"),
+        }
+    }
+}
+
+/// A data file row that failed validation and was not imported.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RowError {
+    /// The zero-based position of the row in the data file.
+    pub row: usize,
+    pub message: String,
+}
+
+/// Progress and outcome counts for a data file import.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ImportReport {
+    pub total_rows: usize,
+    pub processed_rows: usize,
+    pub inserted: u64,
+    pub updated: u64,
+    /// Rows already in the table unchanged, or repeated within the data file.
+    pub skipped: u64,
+    pub invalid: usize,
+    /// The first validation errors, capped at [`MAX_REPORTED_ERRORS`].
+    pub errors: Vec<RowError>,
+}
+
+impl ImportReport {
+    pub fn add_error(&mut self, row: usize, message: String) {
+        self.invalid += 1;
+        if self.errors.len() < MAX_REPORTED_ERRORS {
+            self.errors.push(RowError { row, message });
+        }
+    }
+}
+
+/// A data file import running in the background, polled through its id.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ImportJob {
+    pub id: uuid::Uuid,
+    pub table: ImportTable,
+    pub edition: i32,
+    pub status: ImportStatus,
+    pub report: ImportReport,
+    /// Why the import failed. Batches written before the failure are kept, so re-running the
+    /// import picks up where it stopped.
+    pub error: Option<String>,
+}
+
+impl ImportJob {
+    pub fn new(table: ImportTable, edition: i32) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4(),
+            table,
+            edition,
+            status: ImportStatus::Running,
+            report: ImportReport::default(),
+            error: None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ImportError {
+    /// The data file could not be opened or parsed.
+    File(String),
+    Database(sqlx::Error),
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ImportError::File(e) => write!(f, "Could not read the data file: {}", e),
+            ImportError::Database(e) => write!(f, "Database error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ImportError {}
+
+impl From<sqlx::Error> for ImportError {
+    fn from(e: sqlx::Error) -> Self {
+        ImportError::Database(e)
+    }
+}
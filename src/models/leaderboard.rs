@@ -0,0 +1,61 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    iaaf_points::empty_string_as_none,
+    performance::{Performance, PerformanceRow},
+};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LeaderboardQueryParams {
+    /// Only rank performances in this indoor or outdoor season.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub season: Option<i32>,
+    /// Only rank performances on or after this date.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub from: Option<NaiveDate>,
+    /// Only rank performances on or before this date.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub to: Option<NaiveDate>,
+    /// The page to return, starting at 1.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub page: Option<i64>,
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub page_size: Option<i64>,
+}
+
+/// Raw row returned when ranking athletes: an athlete's best performance with its rank.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct LeaderboardRow {
+    #[sqlx(flatten)]
+    pub performance: PerformanceRow,
+    pub rank: i64,
+    /// The number of ranked athletes across every page.
+    pub total: i64,
+    pub first_name: String,
+    pub last_name: String,
+}
+
+/// An athlete's place on a leaderboard.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LeaderboardEntry {
+    /// Athletes with equal marks share a rank and the ranks after them are skipped.
+    pub rank: i64,
+    pub user_id: i32,
+    pub first_name: String,
+    pub last_name: String,
+    /// The athlete's best performance.
+    pub performance: Performance,
+}
+
+impl From<LeaderboardRow> for LeaderboardEntry {
+    fn from(row: LeaderboardRow) -> Self {
+        Self {
+            rank: row.rank,
+            user_id: row.performance.user_id,
+            first_name: row.first_name,
+            last_name: row.last_name,
+            performance: Performance::from(row.performance),
+        }
+    }
+}
@@ -1,4 +1,8 @@
 pub mod user;
 pub mod iaaf_points;
 pub mod todo;
-pub mod token;
\ No newline at end of file
+pub mod token;
+pub mod import_job;
+pub mod age_grading;
+pub mod performance;
+pub mod leaderboard;
@@ -0,0 +1,298 @@
+use std::str::FromStr;
+
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+
+use crate::scoring::{
+    marks::{format_imperial_mark, format_mark},
+    timing::TimingMethod,
+    track::TrackType,
+    wind::WindStatus,
+    world_rankings::{placing_score, CompetitionCategory},
+};
+
+use super::iaaf_points::{empty_string_as_none, number_or_time, Category, Gender};
+
+/// A result to record for the logged in user.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PerformanceRequest {
+    pub category: Category,
+    /// The gender of the points table to score against, the one on the user's profile when not given.
+    #[serde(default)]
+    pub gender: Option<Gender>,
+    pub event: String,
+    /// Seconds, metres, a clock-style time such as `3:45.12` or feet and inches.
+    #[serde(deserialize_with = "number_or_time")]
+    pub mark: f64,
+    pub date: NaiveDate,
+    #[serde(default)]
+    pub competition: Option<String>,
+    #[serde(default)]
+    pub venue: Option<String>,
+    /// The World Athletics ranking category of the competition, used for the placing score.
+    #[serde(default)]
+    pub competition_category: Option<CompetitionCategory>,
+    /// The round of the competition, e.g. `heat` or `final`.
+    #[serde(default)]
+    pub round: Option<String>,
+    #[serde(default)]
+    pub place: Option<i32>,
+    /// The wind reading in metres per second, for wind assisted events.
+    #[serde(default)]
+    pub wind: Option<f64>,
+    #[serde(default)]
+    pub timing: TimingMethod,
+    /// The track an indoor mark was set on.
+    #[serde(default)]
+    pub track: TrackType,
+    #[serde(default)]
+    pub notes: Option<String>,
+    /// The scoring table edition to score against, the latest one loaded for the event when `None`.
+    #[serde(default)]
+    pub edition: Option<i32>,
+}
+
+/// A performance ready to be written, with the score it earns.
+#[derive(Debug, Clone)]
+pub struct PerformanceRecord {
+    pub category: String,
+    pub gender: String,
+    /// The canonical name of the event, see [`crate::scoring::events`].
+    pub event: String,
+    pub mark: f64,
+    pub date: NaiveDate,
+    pub competition: Option<String>,
+    pub venue: Option<String>,
+    pub competition_category: Option<String>,
+    pub round: Option<String>,
+    pub place: Option<i32>,
+    pub wind: Option<f64>,
+    pub timing: String,
+    pub track: String,
+    pub notes: Option<String>,
+    pub edition: i32,
+    pub scored_mark: f64,
+    pub points: Option<i32>,
+    pub lower_is_better: bool,
+    pub wind_legal: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PerformanceQueryParams {
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub category: Option<Category>,
+    /// Only list performances in this event, matched by canonical name.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub event: Option<String>,
+    /// Only list performances on or after this date.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub from: Option<NaiveDate>,
+    /// Only list performances on or before this date.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub to: Option<NaiveDate>,
+}
+
+/// Raw row of the performances table.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct PerformanceRow {
+    pub id: i32,
+    pub user_id: i32,
+    pub category: String,
+    pub gender: String,
+    pub event: String,
+    pub mark: f64,
+    pub performance_date: Option<NaiveDate>,
+    pub competition: Option<String>,
+    pub venue: Option<String>,
+    pub competition_category: Option<String>,
+    pub round: Option<String>,
+    pub place: Option<i32>,
+    pub wind: Option<f64>,
+    pub timing: String,
+    pub track: String,
+    pub notes: Option<String>,
+    pub edition: Option<i32>,
+    pub scored_mark: Option<f64>,
+    pub points: Option<i32>,
+    pub lower_is_better: bool,
+    pub wind_legal: bool,
+    pub date_created: NaiveDateTime,
+    pub date_modified: Option<NaiveDateTime>,
+}
+
+/// A recorded result along with the points it scored when it was written.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Performance {
+    pub id: i32,
+    pub user_id: i32,
+    pub category: String,
+    pub gender: String,
+    /// The canonical name of the event, see [`crate::scoring::events`].
+    pub event: String,
+    /// The mark as entered.
+    pub mark: f64,
+    pub mark_display: String,
+    pub mark_imperial: Option<String>,
+    /// `None` for performances imported from linked scoring table rows, which had no date.
+    pub date: Option<NaiveDate>,
+    pub competition: Option<String>,
+    pub venue: Option<String>,
+    pub competition_category: Option<CompetitionCategory>,
+    pub round: Option<String>,
+    pub place: Option<i32>,
+    #[serde(flatten)]
+    pub wind: WindStatus,
+    pub timing: TimingMethod,
+    pub track: TrackType,
+    pub notes: Option<String>,
+    /// The scoring table edition the performance was scored against.
+    pub edition: Option<i32>,
+    /// The mark that was scored, after rounding and converting hand times and indoor times on
+    /// non-standard tracks.
+    pub scored_mark: Option<f64>,
+    pub scored_mark_display: Option<String>,
+    /// `None` when the mark is below the lowest entry in the table.
+    pub points: Option<i32>,
+    /// The World Athletics ranking placing score for the place in the competition.
+    pub placing_score: i32,
+    /// The points plus the placing score, `None` when the mark is below the lowest entry in the table.
+    pub ranking_score: Option<i32>,
+    pub lower_is_better: bool,
+    pub date_created: NaiveDateTime,
+    pub date_modified: Option<NaiveDateTime>,
+}
+
+impl From<PerformanceRow> for Performance {
+    fn from(row: PerformanceRow) -> Self {
+        let category = row.category.parse().unwrap_or(Category::Outdoor);
+        let competition_category = row.competition_category.as_deref().and_then(|c| c.parse().ok());
+        let placing_score = placing_score(competition_category, row.place, row.round.as_deref());
+        Self {
+            id: row.id,
+            user_id: row.user_id,
+            mark: row.mark,
+            mark_display: format_mark(row.mark, row.lower_is_better),
+            mark_imperial: format_imperial_mark(row.mark, &row.event, row.lower_is_better),
+            date: row.performance_date,
+            competition: row.competition,
+            venue: row.venue,
+            competition_category,
+            round: row.round,
+            place: row.place,
+            wind: WindStatus { wind_legal: row.wind_legal, ..WindStatus::new(&category, &row.event, row.wind) },
+            timing: row.timing.parse().unwrap_or_default(),
+            track: row.track.parse().unwrap_or_default(),
+            notes: row.notes,
+            edition: row.edition,
+            scored_mark: row.scored_mark,
+            scored_mark_display: row.scored_mark.map(|mark| format_mark(mark, row.lower_is_better)),
+            points: row.points,
+            placing_score,
+            ranking_score: row.points.map(|points| points + placing_score),
+            lower_is_better: row.lower_is_better,
+            date_created: row.date_created,
+            date_modified: row.date_modified,
+            category: row.category,
+            gender: row.gender,
+            event: row.event,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BestsQueryParams {
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub category: Option<Category>,
+    /// Only give bests in this event, matched by canonical name.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub event: Option<String>,
+    /// The season to give season bests for, the current indoor or outdoor season when not given.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub season: Option<i32>,
+}
+
+/// Raw row returned when looking up bests: a performance that is a personal or season best.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct BestRow {
+    #[sqlx(flatten)]
+    pub performance: PerformanceRow,
+    pub personal_best: bool,
+    pub season_best: bool,
+}
+
+/// A user's best performances in one event.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EventBests {
+    pub category: String,
+    pub gender: String,
+    /// The canonical name of the event, see [`crate::scoring::events`].
+    pub event: String,
+    pub season: i32,
+    pub personal_best: Performance,
+    /// `None` when there is no performance in the season.
+    pub season_best: Option<Performance>,
+}
+
+/// How a progression series is reduced, keeping the best performance in each period.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Aggregation {
+    Month,
+    Season,
+}
+
+impl FromStr for Aggregation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "month" => Ok(Aggregation::Month),
+            "season" => Ok(Aggregation::Season),
+            _ => Err(format!("unknown aggregation '{}', expected month or season", s)),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ProgressionQueryParams {
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub category: Option<Category>,
+    /// Only chart this event, matched by canonical name.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub event: Option<String>,
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub from: Option<NaiveDate>,
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub to: Option<NaiveDate>,
+    /// Keep only the best performance per month or season, every performance when not given.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub aggregate: Option<Aggregation>,
+    /// Score every performance against this scoring table edition so the series is comparable,
+    /// the points each performance scored when it was recorded when not given.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub edition: Option<i32>,
+}
+
+impl From<&ProgressionQueryParams> for PerformanceQueryParams {
+    fn from(params: &ProgressionQueryParams) -> Self {
+        Self { category: params.category.clone(), event: params.event.clone(), from: params.from, to: params.to }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RankingQueryParams {
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub category: Option<Category>,
+    /// Only work out the ranking average in this event, matched by canonical name.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub event: Option<String>,
+    /// The last day of the ranking window, today when not given.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub date: Option<NaiveDate>,
+    /// The number of results to average, the event's usual number when not given.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub results: Option<usize>,
+    /// The length of the ranking window in months, the event's usual window when not given.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub months: Option<u32>,
+}
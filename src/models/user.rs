@@ -1,5 +1,6 @@
+use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
-use super::iaaf_points::PointsInsert;
+use super::iaaf_points::{Gender, PointsInsert};
 
 #[derive(Default, Debug, Clone, PartialEq,  Deserialize, sqlx::FromRow, Serialize, Eq)]
 pub struct User {
@@ -9,7 +10,10 @@ pub struct User {
     pub last_name: String,
     pub email: String,
     pub phone: Option<String>,
-    password: String
+    password: String,
+    /// Used to age-grade the user's marks.
+    pub date_of_birth: Option<NaiveDate>,
+    pub gender: Option<String>,
 }
 
 #[derive(Default, Debug, Clone, PartialEq,  Deserialize, Serialize, Eq)]
@@ -20,6 +24,8 @@ pub struct UserDto {
     pub last_name: String,
     pub email: String,
     pub phone: Option<String>,
+    pub date_of_birth: Option<NaiveDate>,
+    pub gender: Option<String>,
 }
 
 impl User {
@@ -35,6 +41,8 @@ impl User {
             last_name: self.last_name.clone(),
             email: self.email.clone(),
             phone: self.phone.clone(),
+            date_of_birth: self.date_of_birth,
+            gender: self.gender.clone(),
         }
     }
 }
@@ -72,11 +80,15 @@ pub struct CreateUserRequest {
     pub email: String,
     pub phone: Option<String>,
     pub password: String,
+    #[serde(default)]
+    pub date_of_birth: Option<NaiveDate>,
+    #[serde(default)]
+    pub gender: Option<Gender>,
 }
 
 impl CreateUserRequest {
     pub fn new(first_name: String, last_name: String, email: String, phone: Option<String>, password: String) -> CreateUserRequest {
-        CreateUserRequest{first_name, last_name, email,phone, password}
+        CreateUserRequest{first_name, last_name, email,phone, password, date_of_birth: None, gender: None}
     }
 }
 
@@ -87,6 +99,10 @@ pub struct UpdateUserRequest {
     pub last_name: String,
     pub email: String,
     pub phone: Option<String>,
+    #[serde(default)]
+    pub date_of_birth: Option<NaiveDate>,
+    #[serde(default)]
+    pub gender: Option<Gender>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -0,0 +1,158 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Extension, Json,
+};
+
+use crate::{
+    models::{
+        age_grading::{AgeGradeQueryParams, AgeGradeResult},
+        iaaf_points::{Category, Gender, MarkQuery},
+    },
+    scoring::{
+        age_grading::{age_grade, age_on},
+        events::{canonical_event, lower_is_better},
+        marks::{format_imperial_mark, format_mark, parse_scorable_mark, round_mark},
+    },
+};
+
+use super::{
+    database_functions::{age_grading_db::get_age_factor, athletics_db::get_points_for_mark},
+    jwt_auth::JWTAuthMiddleware,
+    routes::AppState,
+};
+
+/// Age-grades a mark with the WMA age factors for an athlete's age and gender, and scores the
+/// age-graded mark against the open scoring tables.
+pub async fn get_age_grade_handler(
+    Path((category, gender, event)): Path<(Category, Gender, String)>,
+    Query(params): Query<AgeGradeQueryParams>,
+    State(data): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let Some(age) = params.age else {
+        let bad_json = serde_json::json!({
+            "status": "Bad Request",
+            "message": "The age query parameter is required",
+        });
+        return Err((StatusCode::BAD_REQUEST, Json(bad_json)));
+    };
+    age_grade_response(&data, category, gender, &event, age, &params).await
+}
+
+/// Age-grades a mark for the logged in user, working out their age on the competition date from the
+/// date of birth on their profile. An `age` query parameter overrides the profile.
+pub async fn get_user_age_grade_handler(
+    Extension(jwtauth): Extension<JWTAuthMiddleware>,
+    Path((category, event)): Path<(Category, String)>,
+    Query(params): Query<AgeGradeQueryParams>,
+    State(data): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let bad_request = |message: &str| {
+        let error_response = serde_json::json!({
+            "status": "Bad Request",
+            "message": message,
+        });
+        (StatusCode::BAD_REQUEST, Json(error_response))
+    };
+
+    let user = &jwtauth.user;
+    let Some(gender) = user.gender.as_deref().and_then(|g| g.parse::<Gender>().ok()) else {
+        return Err(bad_request("Add your gender to your profile to age-grade marks"));
+    };
+    let age = match (params.age, user.date_of_birth) {
+        (Some(age), _) => age,
+        (None, Some(date_of_birth)) => {
+            let date = params.date.unwrap_or_else(|| chrono::Local::now().date_naive());
+            age_on(date_of_birth, date)
+        }
+        (None, None) => return Err(bad_request("Add your date of birth to your profile to age-grade marks")),
+    };
+    age_grade_response(&data, category, gender, &event, age, &params).await
+}
+
+async fn age_grade_response(
+    data: &Arc<AppState>,
+    category: Category,
+    gender: Gender,
+    event: &str,
+    age: i32,
+    params: &AgeGradeQueryParams,
+) -> Result<Json<AgeGradeResult>, (StatusCode, Json<serde_json::Value>)> {
+    let mark = params.mark.as_deref().map(parse_scorable_mark).transpose().map_err(|e| {
+        let error_response = serde_json::json!({
+            "status": "Bad Request",
+            "message": format!("Invalid mark: {}", e),
+        });
+        (StatusCode::BAD_REQUEST, Json(error_response))
+    })?;
+    let Some(mark) = mark else {
+        let bad_json = serde_json::json!({
+            "status": "Bad Request",
+            "message": "The mark query parameter is required",
+        });
+        return Err((StatusCode::BAD_REQUEST, Json(bad_json)));
+    };
+
+    let not_found = |message: String| {
+        let error_response = serde_json::json!({
+            "status": "error",
+            "message": message,
+        });
+        (StatusCode::NOT_FOUND, Json(error_response))
+    };
+    let database_error = |e: sqlx::Error| {
+        let error_response = serde_json::json!({
+            "status": "error",
+            "message": format!("Database error: { }", e),
+        });
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    };
+
+    let (Some(canonical), Some(lower_is_better)) = (canonical_event(event), lower_is_better(event)) else {
+        return Err(not_found(format!("No age factors for unknown event {}", event)));
+    };
+    let Some(factor) = get_age_factor(&data.db, &gender, canonical, age, params.factors_edition)
+        .await
+        .map_err(database_error)?
+    else {
+        return Err(not_found(format!("No age factor found for {} {} at age {}", gender, canonical, age)));
+    };
+
+    let timing = params.timing.unwrap_or_default();
+    let timing_adjustment = timing.adjustment(canonical);
+    let track = params.track.unwrap_or_default();
+    let track_factor = track.factor(&category, canonical);
+    let mark = round_mark((mark + timing_adjustment) * track_factor, lower_is_better);
+    let grade = age_grade(mark, lower_is_better, age, factor.factor, factor.open_standard);
+
+    let query = MarkQuery {
+        category,
+        gender: gender.clone(),
+        event: canonical.to_string(),
+        mark: grade.age_graded_mark,
+        edition: params.edition,
+        timing: Default::default(),
+        wind: None,
+        track: Default::default(),
+    };
+    let open_equivalent = get_points_for_mark(&data.db, query).await.map_err(database_error)?;
+
+    Ok(Json(AgeGradeResult {
+        factors_edition: factor.edition.unwrap_or_default(),
+        gender: gender.to_string(),
+        event: canonical.to_string(),
+        mark,
+        mark_display: format_mark(mark, lower_is_better),
+        mark_imperial: format_imperial_mark(mark, canonical, lower_is_better),
+        timing,
+        timing_adjustment,
+        track,
+        track_factor,
+        age_graded_mark_imperial: format_imperial_mark(grade.age_graded_mark, canonical, lower_is_better),
+        grade,
+        open_equivalent,
+    }))
+}
@@ -302,5 +302,5 @@ async fn cache_jwt_token(data: &Arc<AppState>, token: &TokenDetails) {
 /// * `data` - A reference to the `AppState` struct containing the application state.
 /// * `token` - A reference to the `TokenDetails` struct containing the token to be removed.
 async fn remove_jwt_token(data: &Arc<AppState>, token: &uuid::Uuid) {
-    data.cache.remove(&token).await;
+    data.cache.remove(token).await;
 }
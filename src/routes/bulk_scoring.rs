@@ -0,0 +1,158 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::State,
+    http::{header::CONTENT_TYPE, HeaderMap, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+
+use crate::{
+    models::iaaf_points::{BulkScoreResult, MarkQuery},
+    scoring::{
+        marks::{check_mark, parse_scorable_mark},
+        timing::TimingMethod,
+        track::TrackType,
+    },
+};
+
+use super::{database_functions::athletics_db::get_points_for_marks, routes::AppState};
+
+/// The most entries accepted in one bulk scoring request.
+const MAX_BULK_ENTRIES: usize = 10_000;
+
+/// Scores a list of performances in one request.
+///
+/// The body is either a JSON array of `{category, gender, event, mark}` objects or, when sent as
+/// `text/csv`, a CSV file with those columns. Entries may also give an `edition`, a `timing` method,
+/// a `wind` reading and the `track` an indoor mark was set on. Entries that fail to parse or have no points table
+/// are reported individually without failing the rest of the batch.
+pub async fn bulk_score_handler(
+    State(data): State<Arc<AppState>>,
+    headers: HeaderMap,
+    body: String,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let is_csv = headers
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("text/csv"));
+
+    let entries = if is_csv { parse_csv(&body) } else { parse_json(&body) }.map_err(|e| {
+        let error_response = serde_json::json!({
+            "status": "Bad Request",
+            "message": e,
+        });
+        (StatusCode::BAD_REQUEST, Json(error_response))
+    })?;
+
+    if entries.len() > MAX_BULK_ENTRIES {
+        let error_response = serde_json::json!({
+            "status": "Payload Too Large",
+            "message": format!("At most {} entries can be scored at once", MAX_BULK_ENTRIES),
+        });
+        return Err((StatusCode::PAYLOAD_TOO_LARGE, Json(error_response)));
+    }
+
+    let queries: Vec<MarkQuery> = entries.iter().filter_map(|e| e.as_ref().ok().cloned()).collect();
+    let mut scores = get_points_for_marks(&data.db, &queries)
+        .await
+        .map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "error",
+                "message": format!("Database error: { }", e),
+            });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?
+        .into_iter();
+
+    let results: Vec<BulkScoreResult> = entries
+        .into_iter()
+        .enumerate()
+        .map(|(row, entry)| match entry {
+            Ok(entry) => match scores.next().flatten() {
+                Some(score) => BulkScoreResult { row, entry: Some(entry), score: Some(score), error: None },
+                None => {
+                    let error = format!("No points table found for {} {} {}", entry.category, entry.gender, entry.event);
+                    BulkScoreResult { row, entry: Some(entry), score: None, error: Some(error) }
+                }
+            },
+            Err(error) => BulkScoreResult { row, entry: None, score: None, error: Some(error) },
+        })
+        .collect();
+
+    let errors = results.iter().filter(|r| r.error.is_some()).count();
+    let json_response = serde_json::json!({
+        "scored": results.len() - errors,
+        "errors": errors,
+        "results": results,
+    });
+
+    Ok(Json(json_response))
+}
+
+/// Parses a JSON array of entries, keeping a per-entry error for any that do not deserialize or have
+/// a mark that cannot be scored.
+fn parse_json(body: &str) -> Result<Vec<Result<MarkQuery, String>>, String> {
+    let values: Vec<serde_json::Value> =
+        serde_json::from_str(body).map_err(|e| format!("Expected a JSON array of entries: {}", e))?;
+
+    Ok(values
+        .into_iter()
+        .map(|value| {
+            let entry: MarkQuery = serde_json::from_value(value).map_err(|e| e.to_string())?;
+            check_mark(entry.mark).map_err(|e| e.to_string())?;
+            Ok(entry)
+        })
+        .collect())
+}
+
+/// Parses a CSV file with `category`, `gender`, `event` and `mark` columns in any order, plus
+/// optional `edition`, `timing`, `wind` and `track` columns.
+fn parse_csv(body: &str) -> Result<Vec<Result<MarkQuery, String>>, String> {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(body.as_bytes());
+
+    let headers = reader.headers().map_err(|e| format!("Invalid CSV header: {}", e))?.clone();
+    let column = |name: &str| {
+        headers
+            .iter()
+            .position(|h| h.eq_ignore_ascii_case(name))
+            .ok_or_else(|| format!("CSV is missing the '{}' column", name))
+    };
+    let (category, gender, event, mark) = (column("category")?, column("gender")?, column("event")?, column("mark")?);
+    let edition = column("edition").ok();
+    let timing = column("timing").ok();
+    let wind = column("wind").ok();
+    let track = column("track").ok();
+
+    Ok(reader
+        .records()
+        .map(|record| {
+            let record = record.map_err(|e| e.to_string())?;
+            let field = |index: usize| record.get(index).unwrap_or_default();
+            Ok(MarkQuery {
+                category: field(category).parse()?,
+                gender: field(gender).parse()?,
+                event: field(event).to_string(),
+                mark: parse_scorable_mark(field(mark)).map_err(|e| e.to_string())?,
+                edition: match edition.map(field) {
+                    None | Some("") => None,
+                    Some(value) => Some(value.parse().map_err(|_| format!("Invalid edition '{}'", value))?),
+                },
+                timing: match timing.map(field) {
+                    None | Some("") => TimingMethod::default(),
+                    Some(value) => value.parse()?,
+                },
+                wind: match wind.map(field) {
+                    None | Some("") => None,
+                    Some(value) => Some(value.parse().map_err(|_| format!("Invalid wind '{}'", value))?),
+                },
+                track: match track.map(field) {
+                    None | Some("") => TrackType::default(),
+                    Some(value) => value.parse()?,
+                },
+            })
+        })
+        .collect())
+}
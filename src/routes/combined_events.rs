@@ -0,0 +1,46 @@
+use axum::{extract::Path, http::StatusCode, response::IntoResponse, Json};
+
+use crate::{
+    models::iaaf_points::{Category, CombinedEventsRequest, Gender},
+    scoring::{
+        combined_events::{disciplines, score},
+        events::resolve_event,
+    },
+};
+
+/// Scores a decathlon, heptathlon or pentathlon from the individual discipline marks using the
+/// combined events formulas. This does not use the points table.
+pub async fn score_combined_events_handler(
+    Path((category, gender, event)): Path<(Category, Gender, String)>,
+    Json(req): Json<CombinedEventsRequest>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let Some(event_disciplines) = disciplines(&category, &gender, &event) else {
+        let error_response = serde_json::json!({
+            "status": "error",
+            "message": format!("{} {} {} is not a supported combined event", category, gender, event),
+        });
+        return Err((StatusCode::NOT_FOUND, Json(error_response)));
+    };
+
+    let marks: Vec<(String, f64)> = req.marks.into_iter().map(|m| (m.event, m.mark)).collect();
+    match score(event_disciplines, &marks) {
+        Ok(result) => {
+            let json_response = serde_json::json!({
+                "category": category,
+                "gender": gender,
+                "event": resolve_event(&event),
+                "total": result.total,
+                "disciplines": result.disciplines,
+                "missing": result.missing,
+            });
+            Ok(Json(json_response))
+        }
+        Err(e) => {
+            let error_response = serde_json::json!({
+                "status": "Bad Request",
+                "message": e.to_string(),
+            });
+            Err((StatusCode::BAD_REQUEST, Json(error_response)))
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use std::collections::hash_map::DefaultHasher;
+use std::fs::File;
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+
+use sqlx::{Pool, Postgres};
+
+use super::import_db::{import_rows, ImportRow};
+use crate::models::age_grading::{AgeFactor, DEFAULT_AGE_FACTOR_EDITION};
+use crate::models::iaaf_points::Gender;
+use crate::models::import_job::{ImportError, ImportReport};
+use crate::routes::utils::data_file::DataFileFormat;
+use crate::scoring::events::canonical_event;
+
+/// Imports a WMA age factor edition from its data file, see [`import_age_factor_file`].
+pub async fn read_age_factors_into_db(
+    pool: &Pool<Postgres>,
+    edition: i32,
+    progress: impl Fn(&ImportReport),
+) -> Result<ImportReport, ImportError> {
+    let location = age_factor_file_location(edition);
+    import_age_factor_file(pool, edition, Path::new(&location), DataFileFormat::from_file_name(&location), progress).await
+}
+
+/// Upserts WMA age factor rows on their natural key: edition, gender, event and age.
+///
+/// Rows are streamed and written in batches, see [`import_rows`]. Events are stored under their
+/// canonical name and must be in the event registry, as grading needs to know whether lower marks
+/// are better. An age listed more than once keeps its first row.
+///
+/// # Arguments
+/// * `pool` - A reference to the Postgres connection pool.
+/// * `edition` - The age factor edition the rows belong to.
+/// * `path` - The JSON or CSV data file.
+/// * `format` - The format of the file.
+/// * `progress` - Called with the running totals after every batch.
+pub async fn import_age_factor_file(
+    pool: &Pool<Postgres>,
+    edition: i32,
+    path: &Path,
+    format: DataFileFormat,
+    progress: impl Fn(&ImportReport),
+) -> Result<ImportReport, ImportError> {
+    let file = File::open(path).map_err(|e| ImportError::File(e.to_string()))?;
+    import_rows::<AgeFactor>(pool, edition, file, format, progress).await
+}
+
+impl ImportRow for AgeFactor {
+    fn prepare(&mut self, edition: i32) -> Result<(), String> {
+        self.gender = self.gender.parse::<Gender>()?.to_string();
+        self.event = canonical_event(&self.event)
+            .ok_or_else(|| format!("unknown event '{}'", self.event))?
+            .to_string();
+        if !(5..=110).contains(&self.age) {
+            return Err(format!("age must be between 5 and 110, got {}", self.age));
+        }
+        if !self.factor.is_finite() || self.factor <= 0.0 || self.factor > 1.0 {
+            return Err(format!("factor must be above 0 and at most 1, got {}", self.factor));
+        }
+        if !self.open_standard.is_finite() || self.open_standard <= 0.0 {
+            return Err(format!("open standard must be a positive number, got {}", self.open_standard));
+        }
+        match self.edition {
+            Some(row_edition) if row_edition != edition => {
+                Err(format!("row belongs to edition {}, not {}", row_edition, edition))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn natural_key(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        (&self.gender, &self.event, self.age).hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn rank(&self) -> i64 {
+        0
+    }
+
+    async fn upsert(pool: &Pool<Postgres>, edition: i32, batch: &[Self]) -> Result<Vec<bool>, sqlx::Error> {
+        sqlx::query_scalar(
+            r#"INSERT INTO age_factors (gender, event, age, factor, open_standard, edition)
+                SELECT *, $6 FROM UNNEST($1::VARCHAR(10)[], $2::VARCHAR(20)[], $3::INTEGER[], $4::FLOAT[], $5::FLOAT[])
+            ON CONFLICT (edition, gender, event, age) DO UPDATE
+                SET factor = EXCLUDED.factor, open_standard = EXCLUDED.open_standard
+                WHERE (age_factors.factor, age_factors.open_standard) IS DISTINCT FROM (EXCLUDED.factor, EXCLUDED.open_standard)
+            RETURNING xmax = 0"#,
+        )
+        .bind(batch.iter().map(|f| f.gender.clone()).collect::<Vec<String>>())
+        .bind(batch.iter().map(|f| f.event.clone()).collect::<Vec<String>>())
+        .bind(batch.iter().map(|f| f.age).collect::<Vec<i32>>())
+        .bind(batch.iter().map(|f| f.factor).collect::<Vec<f64>>())
+        .bind(batch.iter().map(|f| f.open_standard).collect::<Vec<f64>>())
+        .bind(edition)
+        .fetch_all(pool)
+        .await
+    }
+}
+
+static AGE_FACTOR_FILE_LOCATION: &str = "data/WmaAgeFactors.json";
+
+/// The data file for a WMA age factor edition, `data/WmaAgeFactors_<edition>.json` or `.csv`. The
+/// default edition falls back to `data/WmaAgeFactors.json` when it has no file of its own.
+pub fn age_factor_file_location(edition: i32) -> String {
+    let json = format!("data/WmaAgeFactors_{}.json", edition);
+    let csv = format!("data/WmaAgeFactors_{}.csv", edition);
+    if Path::new(&json).exists() {
+        json
+    } else if Path::new(&csv).exists() {
+        csv
+    } else if edition == DEFAULT_AGE_FACTOR_EDITION {
+        AGE_FACTOR_FILE_LOCATION.to_string()
+    } else {
+        json
+    }
+}
+
+/// Retrieves the age factor for an athlete's age in an event.
+///
+/// # Arguments
+/// * `pool` - A reference to the Postgres connection pool.
+/// * `gender` - The athlete's gender.
+/// * `event` - The event, resolved to its canonical name.
+/// * `age` - The athlete's age on the day of the competition.
+/// * `edition` - The age factor edition, the latest one loaded for the event when `None`.
+///
+/// # Returns
+/// The age factor row, or `None` if the edition has no factor for the age in the event.
+pub async fn get_age_factor(
+    pool: &Pool<Postgres>,
+    gender: &Gender,
+    event: &str,
+    age: i32,
+    edition: Option<i32>,
+) -> Result<Option<AgeFactor>, sqlx::Error> {
+    sqlx::query_as(
+        r#"SELECT gender, event, age, factor, open_standard, edition FROM age_factors
+        WHERE gender = $1 AND event = $2 AND age = $3 AND
+            edition = COALESCE($4, (SELECT MAX(edition) FROM age_factors WHERE gender = $1 AND event = $2))"#,
+    )
+    .bind(gender.to_string())
+    .bind(event)
+    .bind(age)
+    .bind(edition)
+    .fetch_optional(pool)
+    .await
+}
+
@@ -1,68 +1,615 @@
-use std::error::Error;
+use std::collections::hash_map::DefaultHasher;
+use std::fs::File;
+use std::hash::{Hash, Hasher};
+use std::io::Read;
+use std::path::Path;
 
-use std::time::SystemTime;
-use tokio::fs::File;
-use tokio::io::{AsyncReadExt, BufReader};
 use sqlx::{Pool, Postgres};
-use crate::models::iaaf_points::PointsInsert;
+use crate::routes::utils::data_file::DataFileFormat;
+use crate::scoring::{events::{canonical_event, resolve_event}, wind::WindStatus};
+use crate::models::import_job::{ImportError, ImportReport};
+use super::import_db::{import_rows, ImportRow};
+use crate::models::iaaf_points::{Category, CurvePointRow, CurveSlice, EditionSummary, EventSummary, EventSummaryRow, Gender, MarkLookupRow, MarkQuery, MarkRange, MarkRangeRow, MarkScore, PointsInsert, RepresentativeMark, DEFAULT_EDITION};
 
-pub async fn read_into_db(pool : &Pool<Postgres>) -> bool{
-    let count: i64 = sqlx::query_scalar(r#"SELECT COUNT(id) FROM points"#)
-        .fetch_one(pool)
-        .await
-        .unwrap();
+/// Imports a scoring table edition from its data file, see [`import_points`].
+pub async fn read_into_db(
+    pool: &Pool<Postgres>,
+    edition: i32,
+    progress: impl Fn(&ImportReport),
+) -> Result<ImportReport, ImportError> {
+    let location = edition_file_location(edition);
+    import_file(pool, edition, Path::new(&location), DataFileFormat::from_file_name(&location), progress).await
+}
 
-    if count > 200000 {
-        return false;
+/// Imports a scoring table file into an edition, see [`import_points`].
+pub async fn import_file(
+    pool: &Pool<Postgres>,
+    edition: i32,
+    path: &Path,
+    format: DataFileFormat,
+    progress: impl Fn(&ImportReport),
+) -> Result<ImportReport, ImportError> {
+    let file = File::open(path).map_err(|e| ImportError::File(e.to_string()))?;
+    import_points(pool, edition, file, format, progress).await
+}
+
+/// Upserts scoring table rows on their natural key: edition, gender, category, event and mark.
+///
+/// Rows are streamed and written in batches, see [`import_rows`]. A mark listed more than once keeps
+/// its highest points value. Event names are stored under their canonical name, and rows stored
+/// under an alias are renamed first so they match.
+///
+/// # Arguments
+/// * `pool` - A reference to the Postgres connection pool.
+/// * `edition` - The scoring table edition the rows belong to.
+/// * `reader` - The JSON or CSV file contents.
+/// * `format` - The format of `reader`.
+/// * `progress` - Called with the running totals after every batch.
+pub async fn import_points(
+    pool: &Pool<Postgres>,
+    edition: i32,
+    reader: impl Read + Send + 'static,
+    format: DataFileFormat,
+    progress: impl Fn(&ImportReport),
+) -> Result<ImportReport, ImportError> {
+    canonicalize_event_names(pool).await?;
+
+    let result = import_rows::<PointsInsert>(pool, edition, reader, format, progress).await;
+    // A failed import may still have written some batches.
+    if !matches!(&result, Ok(report) if report.inserted + report.updated == 0) {
+        update_mark_times(pool).await?;
     }
+    result
+}
 
-    let models: Result<Vec<PointsInsert>, Box<dyn Error>> = read_file_async().await;
+impl ImportRow for PointsInsert {
+    fn prepare(&mut self, edition: i32) -> Result<(), String> {
+        self.event = resolve_event(&self.event);
+        validate_points_row(self, edition)
+    }
 
-    return match models {
-        Err(e) => {
-            false
-        }
-        Ok(_) => {
-            print!("inserting into database");
-            //There has to be a better way to insert 200000 records than individually. It takes so long.
-            let models: Vec<PointsInsert> = models.unwrap_or_default();
-
-            let points: Vec<i32> = models.iter().map(|p| p.points).collect();
-            let genders: Vec<String> = models.iter().map(|p| p.gender.clone()).collect();
-            let categories: Vec<String> = models.iter().map(|p| p.category.clone()).collect();
-            let events: Vec<String> = models.iter().map(|p| p.event.clone()).collect();
-            let marks: Vec<f64> = models.iter().map(|p| p.mark).collect();
-            let start = SystemTime::now();
-            let query_result = sqlx::query(
-                r#"INSERT INTO points (points, gender, category, event, mark)
-                    SELECT * FROM UNNEST($1::INTEGER[], $2::VARCHAR(10)[], $3::VARCHAR(20)[], $4::VARCHAR(10)[], $5::Float[])"#,
-            )
-                .bind(points)
-                .bind(genders)
-                .bind(categories)
-                .bind(events)
-                .bind(marks)
-                .execute(pool)
-                .await;
-
-            match query_result {
-                Ok(_) => true,
-                Err(_) => false
-            }
+    fn natural_key(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        (&self.gender, &self.category, &self.event, self.mark.to_bits()).hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn rank(&self) -> i64 {
+        self.points.into()
+    }
+
+    async fn upsert(pool: &Pool<Postgres>, edition: i32, batch: &[Self]) -> Result<Vec<bool>, sqlx::Error> {
+        sqlx::query_scalar(
+            r#"INSERT INTO points (points, gender, category, event, mark, edition)
+                SELECT *, $6 FROM UNNEST($1::INTEGER[], $2::VARCHAR(10)[], $3::VARCHAR(20)[], $4::VARCHAR(20)[], $5::Float[])
+            ON CONFLICT (edition, gender, category, event, mark) DO UPDATE SET points = EXCLUDED.points
+                WHERE points.points IS DISTINCT FROM EXCLUDED.points
+            RETURNING xmax = 0"#,
+        )
+            .bind(batch.iter().map(|p| p.points).collect::<Vec<i32>>())
+            .bind(batch.iter().map(|p| p.gender.clone()).collect::<Vec<String>>())
+            .bind(batch.iter().map(|p| p.category.clone()).collect::<Vec<String>>())
+            .bind(batch.iter().map(|p| p.event.clone()).collect::<Vec<String>>())
+            .bind(batch.iter().map(|p| p.mark).collect::<Vec<f64>>())
+            .bind(edition)
+            .fetch_all(pool)
+            .await
+    }
+}
+
+/// Renames events stored under an alias to their canonical name. Rows that would clash with a row
+/// already stored under the canonical name are left as they are.
+///
+/// # Returns
+/// The number of rows renamed.
+pub async fn canonicalize_event_names(pool: &Pool<Postgres>) -> Result<u64, sqlx::Error> {
+    let names: Vec<String> = sqlx::query_scalar(r#"SELECT DISTINCT event FROM points WHERE event IS NOT NULL"#)
+        .fetch_all(pool)
+        .await?;
+
+    let (aliases, canonical): (Vec<String>, Vec<String>) = names
+        .into_iter()
+        .filter_map(|name| {
+            let canonical = canonical_event(&name)?;
+            (canonical != name).then(|| (name, canonical.to_string()))
+        })
+        .unzip();
+    if aliases.is_empty() {
+        return Ok(0);
+    }
+
+    let result = sqlx::query(
+        r#"UPDATE points p SET event = r.canonical
+        FROM UNNEST($1::VARCHAR[], $2::VARCHAR[]) AS r(alias, canonical)
+        WHERE p.event = r.alias AND NOT EXISTS (
+            SELECT 1 FROM points q
+            WHERE q.edition = p.edition AND q.gender = p.gender AND q.category = p.category AND
+                q.mark = p.mark AND q.event = r.canonical
+        )"#,
+    )
+    .bind(aliases)
+    .bind(canonical)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Checks a data file row before it is imported into an edition.
+fn validate_points_row(model: &PointsInsert, edition: i32) -> Result<(), String> {
+    model.gender.parse::<Gender>()?;
+    model.category.parse::<Category>()?;
+    if model.event.trim().is_empty() || model.event.len() > 20 {
+        return Err(format!("invalid event name '{}'", model.event));
+    }
+    if model.points <= 0 {
+        return Err(format!("points must be positive, got {}", model.points));
+    }
+    if !model.mark.is_finite() || model.mark <= 0.0 {
+        return Err(format!("mark must be a positive number, got {}", model.mark));
+    }
+    match model.edition {
+        Some(row_edition) if row_edition != edition => {
+            Err(format!("row belongs to edition {}, not {}", row_edition, edition))
         }
-    };
+        _ => Ok(()),
+    }
+}
+
+static FILE_LOCATION: &str = "data/WorldAthletics.json";
+
+/// The data file for a scoring table edition, `data/WorldAthletics_<edition>.json` or `.csv`. The
+/// default edition falls back to the original `data/WorldAthletics.json` when it has no file of its own.
+pub fn edition_file_location(edition: i32) -> String {
+    let json = format!("data/WorldAthletics_{}.json", edition);
+    let csv = format!("data/WorldAthletics_{}.csv", edition);
+    if Path::new(&json).exists() {
+        json
+    } else if Path::new(&csv).exists() {
+        csv
+    } else if edition == DEFAULT_EDITION {
+        FILE_LOCATION.to_string()
+    } else {
+        json
+    }
+}
+
+/// Retrieves every row of one edition of the points table. When `None`, each event's rows come from
+/// the latest edition loaded for that event.
+pub async fn get_all_points(pool: &Pool<Postgres>, edition: Option<i32>) -> Result<Vec<PointsInsert>, sqlx::Error> {
+    sqlx::query_as(
+        r#"SELECT id, points, gender, category, event, mark, edition FROM (
+            SELECT *, MAX(edition) OVER (PARTITION BY category, gender, event) AS latest_edition FROM points
+            WHERE mark IS NOT NULL AND points IS NOT NULL
+        ) p
+        WHERE edition = COALESCE($1, latest_edition)"#,
+    )
+    .bind(edition)
+    .fetch_all(pool)
+    .await
+}
 
-    static FILE_LOCATION: &str = "data/WorldAthletics.json";
+/// Lists the scoring table editions loaded into the points table, newest first.
+pub async fn get_editions(pool: &Pool<Postgres>) -> Result<Vec<EditionSummary>, sqlx::Error> {
+    sqlx::query_as(
+        r#"SELECT edition, COUNT(DISTINCT (category, gender, event)) AS events, COUNT(*) AS rows
+        FROM points
+        GROUP BY edition
+        ORDER BY edition DESC"#,
+    )
+    .fetch_all(pool)
+    .await
+}
 
-    async fn read_file_async() -> Result<Vec<PointsInsert>, Box<dyn Error>> {
-        let file = File::open(FILE_LOCATION).await?;
-        let mut reader = BufReader::new(file);
-        let mut buffer = Vec::new();
-        reader.read_to_end(&mut buffer).await?;
+/// Fills the `mark_time` column from `mark` for timed events so clock-style times are stored
+/// alongside the seconds used for scoring. Measured events have no time and are set to `NULL`.
+pub async fn update_mark_times(pool: &Pool<Postgres>) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        WITH directions AS (
+            SELECT edition, category, gender, event,
+                (ARRAY_AGG(mark ORDER BY points DESC))[1] < (ARRAY_AGG(mark ORDER BY points ASC))[1] AS lower_is_better
+            FROM points
+            WHERE mark IS NOT NULL
+            GROUP BY edition, category, gender, event
+        )
+        UPDATE points p
+        SET mark_time = CASE
+            WHEN d.lower_is_better AND p.mark < 86400 THEN MAKE_INTERVAL(secs => p.mark)::TIME
+            ELSE NULL
+        END
+        FROM directions d
+        WHERE p.edition = d.edition AND p.category = d.category AND p.gender = d.gender AND p.event = d.event;"#,
+    )
+    .execute(pool)
+    .await?;
 
-        let points: Vec<PointsInsert> = serde_json::from_slice(&buffer)?;
+    Ok(result.rows_affected())
+}
+
+/// Scores a mark against the points table for an event.
+///
+/// See [`get_points_for_marks`] for the rounding and lookup rules.
+///
+/// # Returns
+/// `None` if the event has no rows in the points table.
+pub async fn get_points_for_mark(pool: &Pool<Postgres>, query: MarkQuery) -> Result<Option<MarkScore>, sqlx::Error> {
+    let mut scores = get_points_for_marks(pool, &[query]).await?;
+
+    Ok(scores.pop().flatten())
+}
+
+/// Scores a list of marks against the points table in a single round trip.
+///
+/// Whether lower marks are better is worked out from each event's table. Marks are rounded the way
+/// World Athletics rounds results (up to the next hundredth for timed events, down to the
+/// centimetre for measured ones) and then scored against the bracketing rows: a mark earns the
+/// highest points value whose table mark it meets. The nearest better table mark is returned as
+/// the next point. Hand times are converted to automatic times and indoor times to a standard
+/// banked track before rounding.
+///
+/// # Arguments
+/// * `pool` - A reference to the Postgres connection pool.
+/// * `queries` - The marks to score, with event names matched case insensitively. Queries without an
+///   edition are scored against the latest edition loaded for their event.
+///
+/// # Returns
+/// One entry per query in the same order, `None` where the event has no rows in the points table.
+pub async fn get_points_for_marks(
+    pool: &Pool<Postgres>,
+    queries: &[MarkQuery],
+) -> Result<Vec<Option<MarkScore>>, sqlx::Error> {
+    let indexes: Vec<i32> = (0..queries.len() as i32).collect();
+    let categories: Vec<String> = queries.iter().map(|q| q.category.to_string()).collect();
+    let genders: Vec<String> = queries.iter().map(|q| q.gender.to_string()).collect();
+    let events: Vec<String> = queries.iter().map(|q| resolve_event(&q.event)).collect();
+    let marks: Vec<f64> = queries
+        .iter()
+        .map(|q| (q.mark + q.timing.adjustment(&q.event)) * q.track.factor(&q.category, &q.event))
+        .collect();
+    let editions: Vec<Option<i32>> = queries.iter().map(|q| q.edition).collect();
+
+    let rows: Vec<MarkLookupRow> = sqlx::query_as(
+        r#"
+        WITH input AS (
+            SELECT idx, t.event AS event_name, LOWER(t.category) AS category, LOWER(t.gender) AS gender,
+                LOWER(t.event) AS event, t.mark,
+                COALESCE(t.edition, (
+                    SELECT MAX(p.edition) FROM points p
+                    WHERE LOWER(p.category) = LOWER(t.category) AND LOWER(p.gender) = LOWER(t.gender) AND
+                        LOWER(p.event) = LOWER(t.event)
+                )) AS edition
+            FROM UNNEST($1::INTEGER[], $2::VARCHAR[], $3::VARCHAR[], $4::VARCHAR[], $5::FLOAT[], $6::INTEGER[])
+                AS t(idx, category, gender, event, mark, edition)
+        ),
+        event_points AS (
+            SELECT p.id, p.points, p.gender, p.category, p.event, p.mark, p.edition,
+                LOWER(p.category) AS category_key, LOWER(p.gender) AS gender_key, LOWER(p.event) AS event_key
+            FROM points p
+            WHERE p.mark IS NOT NULL AND (p.edition, LOWER(p.category), LOWER(p.gender), LOWER(p.event)) IN (
+                SELECT edition, category, gender, event FROM input
+            )
+        ),
+        directions AS (
+            SELECT edition, category_key, gender_key, event_key,
+                (ARRAY_AGG(mark ORDER BY points DESC))[1] < (ARRAY_AGG(mark ORDER BY points ASC))[1] AS lower_is_better
+            FROM event_points
+            GROUP BY edition, category_key, gender_key, event_key
+        ),
+        rounded AS (
+            SELECT i.idx, i.edition, i.event_name, i.category, i.gender, i.event, d.lower_is_better,
+                (CASE WHEN d.lower_is_better THEN CEIL(ROUND(i.mark::numeric * 100, 6))
+                    ELSE FLOOR(ROUND(i.mark::numeric * 100, 6))
+                END / 100)::FLOAT AS mark
+            FROM input i
+            JOIN directions d ON d.edition = i.edition AND d.category_key = i.category AND
+                d.gender_key = i.gender AND d.event_key = i.event
+        )
+        SELECT r.idx, r.edition, r.event_name, r.lower_is_better, r.mark AS rounded_mark,
+            s.id, s.points, s.gender, s.category, s.event, s.mark,
+            n.points AS next_points, n.mark AS next_mark
+        FROM rounded r
+        LEFT JOIN LATERAL (
+            SELECT * FROM event_points e
+            WHERE e.edition = r.edition AND e.category_key = r.category AND e.gender_key = r.gender AND
+                e.event_key = r.event AND
+                CASE WHEN r.lower_is_better THEN e.mark >= r.mark ELSE e.mark <= r.mark END
+            ORDER BY e.points DESC
+            FETCH FIRST 1 ROWS ONLY
+        ) s ON TRUE
+        LEFT JOIN LATERAL (
+            SELECT * FROM event_points e
+            WHERE e.edition = r.edition AND e.category_key = r.category AND e.gender_key = r.gender AND
+                e.event_key = r.event AND
+                CASE WHEN r.lower_is_better THEN e.mark < r.mark ELSE e.mark > r.mark END
+            ORDER BY
+                CASE WHEN r.lower_is_better THEN -e.mark ELSE e.mark END,
+                e.points DESC
+            FETCH FIRST 1 ROWS ONLY
+        ) n ON TRUE;"#,
+    )
+    .bind(indexes)
+    .bind(categories)
+    .bind(genders)
+    .bind(events)
+    .bind(marks)
+    .bind(editions)
+    .fetch_all(pool)
+    .await?;
 
-        Ok(points)
+    let mut scores: Vec<Option<MarkScore>> = vec![None; queries.len()];
+    for row in rows {
+        let index = row.idx as usize;
+        let query = &queries[index];
+        scores[index] = Some(MarkScore {
+            timing: query.timing,
+            timing_adjustment: query.timing.adjustment(&query.event),
+            track: query.track,
+            track_factor: query.track.factor(&query.category, &query.event),
+            wind: WindStatus::new(&query.category, &query.event, query.wind),
+            ..MarkScore::from(row)
+        });
     }
 
-}
\ No newline at end of file
+    Ok(scores)
+}
+
+/// Finds the range of marks that earn a points value in an event.
+///
+/// Several marks can map to one points value and some points values are skipped entirely, since a
+/// hundredth or a centimetre can be worth more than a point. A points value is achievable when the
+/// next row up the table has a strictly better mark. The achievable points value closest to the
+/// requested one is used, preferring the higher value on a tie.
+///
+/// # Arguments
+/// * `pool` - A reference to the Postgres connection pool.
+/// * `category` - The indoor/outdoor category of the table.
+/// * `gender` - The gender of the table.
+/// * `event` - The event name, matched case insensitively.
+/// * `points` - The points value to look up.
+/// * `edition` - The scoring table edition, the latest one loaded for each event when `None`.
+///
+/// # Returns
+/// `None` if the event has no rows in the points table.
+pub async fn get_marks_for_points(
+    pool: &Pool<Postgres>,
+    category: &Category,
+    gender: &Gender,
+    event: &str,
+    points: i32,
+    edition: Option<i32>,
+) -> Result<Option<MarkRange>, sqlx::Error> {
+    let row: Option<MarkRangeRow> = sqlx::query_as(
+        r#"
+        WITH event_points AS (
+            SELECT id, points, gender, category, event, mark, edition FROM points
+            WHERE
+                edition = COALESCE($5, (
+                    SELECT MAX(edition) FROM points
+                    WHERE LOWER(category) = LOWER($1) AND LOWER(gender) = LOWER($2) AND LOWER(event) = LOWER($3)
+                )) AND
+                LOWER(category) = LOWER($1) AND
+                LOWER(gender) = LOWER($2) AND
+                LOWER(event) = LOWER($3) AND
+                mark IS NOT NULL
+        ),
+        direction AS (
+            SELECT (ARRAY_AGG(mark ORDER BY points DESC))[1] < (ARRAY_AGG(mark ORDER BY points ASC))[1] AS lower_is_better
+            FROM event_points
+        ),
+        ranged AS (
+            SELECT e.*, d.lower_is_better, LEAD(e.mark) OVER (ORDER BY e.points, e.id) AS next_mark,
+                LEAD(e.points) OVER (ORDER BY e.points, e.id) AS next_points
+            FROM event_points e, direction d
+        )
+        SELECT edition, lower_is_better, id, points, gender, category, event, mark,
+            next_points, next_mark
+        FROM ranged
+        WHERE next_mark IS NULL OR
+            CASE WHEN lower_is_better THEN next_mark < mark ELSE next_mark > mark END
+        ORDER BY ABS(points - $4), points DESC
+        FETCH FIRST 1 ROWS ONLY;"#,
+    )
+    .bind(category.to_string())
+    .bind(gender.to_string())
+    .bind(resolve_event(event))
+    .bind(points)
+    .bind(edition)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(|row| MarkRange::from_row(points, row)))
+}
+
+/// Reads a slice of an event's points table for charting, ordered by points.
+///
+/// # Arguments
+/// * `pool` - A reference to the Postgres connection pool.
+/// * `category` - The indoor/outdoor category of the table.
+/// * `gender` - The gender of the table.
+/// * `event` - The event name, matched case insensitively.
+/// * `slice` - The edition, the points and mark ranges (unbounded where `None`) and the step. The
+///   last row of the range is always kept so the curve reaches its end.
+///
+/// # Returns
+/// An empty list if the event has no rows in the range.
+pub async fn get_scoring_curve(
+    pool: &Pool<Postgres>,
+    category: &Category,
+    gender: &Gender,
+    event: &str,
+    slice: &CurveSlice,
+) -> Result<Vec<CurvePointRow>, sqlx::Error> {
+    sqlx::query_as(
+        r#"
+        WITH event_points AS (
+            SELECT id, points, event, mark, edition FROM points
+            WHERE
+                edition = COALESCE($4, (
+                    SELECT MAX(edition) FROM points
+                    WHERE LOWER(category) = LOWER($1) AND LOWER(gender) = LOWER($2) AND LOWER(event) = LOWER($3)
+                )) AND
+                LOWER(category) = LOWER($1) AND
+                LOWER(gender) = LOWER($2) AND
+                LOWER(event) = LOWER($3) AND
+                mark IS NOT NULL AND points IS NOT NULL
+        ),
+        direction AS (
+            SELECT (ARRAY_AGG(mark ORDER BY points DESC))[1] < (ARRAY_AGG(mark ORDER BY points ASC))[1] AS lower_is_better
+            FROM event_points
+        ),
+        sliced AS (
+            SELECT e.*, ROW_NUMBER() OVER (ORDER BY e.points, e.id) AS idx, COUNT(*) OVER () AS total
+            FROM event_points e
+            WHERE
+                ($5::INTEGER IS NULL OR e.points >= $5) AND
+                ($6::INTEGER IS NULL OR e.points <= $6) AND
+                ($7::FLOAT IS NULL OR e.mark >= $7) AND
+                ($8::FLOAT IS NULL OR e.mark <= $8)
+        )
+        SELECT s.edition, s.event, d.lower_is_better, s.points, s.mark
+        FROM sliced s, direction d
+        WHERE (s.idx - 1) % $9 = 0 OR s.idx = s.total
+        ORDER BY s.points, s.id;"#,
+    )
+    .bind(category.to_string())
+    .bind(gender.to_string())
+    .bind(resolve_event(event))
+    .bind(slice.edition)
+    .bind(slice.min_points)
+    .bind(slice.max_points)
+    .bind(slice.min_mark)
+    .bind(slice.max_mark)
+    .bind(slice.step)
+    .fetch_all(pool)
+    .await
+}
+
+/// Finds the marks that earn a points value in every event of a category and gender, using the
+/// closest achievable points value in each event as [`get_marks_for_points`] does.
+///
+/// # Arguments
+/// * `pool` - A reference to the Postgres connection pool.
+/// * `category` - The indoor/outdoor category of the tables.
+/// * `gender` - The gender of the tables.
+/// * `points` - The points value to look up.
+/// * `edition` - The scoring table edition, the latest one loaded for each event when `None`.
+/// * `events` - Only look up these events when set, matched by canonical name.
+pub async fn get_equivalent_marks(
+    pool: &Pool<Postgres>,
+    category: &Category,
+    gender: &Gender,
+    points: i32,
+    edition: Option<i32>,
+    events: Option<&[String]>,
+) -> Result<Vec<MarkRange>, sqlx::Error> {
+    let events: Option<Vec<String>> =
+        events.map(|events| events.iter().map(|e| resolve_event(e).to_lowercase()).collect());
+
+    let rows: Vec<MarkRangeRow> = sqlx::query_as(
+        r#"
+        WITH event_points AS (
+            SELECT id, points, gender, category, event, mark, edition FROM (
+                SELECT *, MAX(edition) OVER (PARTITION BY category, gender, event) AS latest_edition FROM points
+                WHERE
+                    LOWER(category) = LOWER($1) AND
+                    LOWER(gender) = LOWER($2) AND
+                    ($5::VARCHAR[] IS NULL OR LOWER(event) = ANY($5)) AND
+                    mark IS NOT NULL
+            ) p
+            WHERE edition = COALESCE($4, latest_edition)
+        ),
+        directions AS (
+            SELECT event,
+                (ARRAY_AGG(mark ORDER BY points DESC))[1] < (ARRAY_AGG(mark ORDER BY points ASC))[1] AS lower_is_better
+            FROM event_points
+            GROUP BY event
+        ),
+        ranged AS (
+            SELECT e.*, d.lower_is_better, LEAD(e.mark) OVER (PARTITION BY e.event ORDER BY e.points, e.id) AS next_mark,
+                LEAD(e.points) OVER (PARTITION BY e.event ORDER BY e.points, e.id) AS next_points
+            FROM event_points e
+            JOIN directions d ON d.event = e.event
+        )
+        SELECT DISTINCT ON (event) edition, lower_is_better, id, points, gender, category, event, mark,
+            next_points, next_mark
+        FROM ranged
+        WHERE next_mark IS NULL OR
+            CASE WHEN lower_is_better THEN next_mark < mark ELSE next_mark > mark END
+        ORDER BY event, ABS(points - $3), points DESC;"#,
+    )
+    .bind(category.to_string())
+    .bind(gender.to_string())
+    .bind(points)
+    .bind(edition)
+    .bind(events)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.into_iter().map(|row| MarkRange::from_row(points, row)).collect())
+}
+
+/// Lists the events in the points table with the range of marks and points each one covers.
+///
+/// # Arguments
+/// * `pool` - A reference to the Postgres connection pool.
+/// * `category` - Only list events in this category when set.
+/// * `gender` - Only list events for this gender when set.
+/// * `edition` - The scoring table edition, the latest one loaded for each event when `None`.
+pub async fn get_event_catalog(
+    pool: &Pool<Postgres>,
+    category: Option<&Category>,
+    gender: Option<&Gender>,
+    edition: Option<i32>,
+) -> Result<Vec<EventSummary>, sqlx::Error> {
+    let rows: Vec<EventSummaryRow> = sqlx::query_as(
+        r#"
+        SELECT edition, category, gender, event,
+            (ARRAY_AGG(mark ORDER BY points DESC))[1] < (ARRAY_AGG(mark ORDER BY points ASC))[1] AS lower_is_better,
+            MIN(mark) AS min_mark, MAX(mark) AS max_mark,
+            MIN(points) AS min_points, MAX(points) AS max_points
+        FROM (
+            SELECT *, MAX(edition) OVER (PARTITION BY category, gender, event) AS latest_edition FROM points
+            WHERE
+                mark IS NOT NULL AND
+                ($1::VARCHAR IS NULL OR LOWER(category) = LOWER($1)) AND
+                ($2::VARCHAR IS NULL OR LOWER(gender) = LOWER($2))
+        ) p
+        WHERE edition = COALESCE($3, latest_edition)
+        GROUP BY edition, category, gender, event
+        ORDER BY category, gender, event;"#,
+    )
+    .bind(category.map(|c| c.to_string()))
+    .bind(gender.map(|g| g.to_string()))
+    .bind(edition)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.into_iter().map(EventSummary::from).collect())
+}
+
+/// Finds, for every event in an edition, the table mark closest to each of the given points values.
+///
+/// # Arguments
+/// * `pool` - A reference to the Postgres connection pool.
+/// * `edition` - The scoring table edition to take the marks from.
+/// * `points` - The points values to find marks for.
+pub async fn get_representative_marks(
+    pool: &Pool<Postgres>,
+    edition: i32,
+    points: &[i32],
+) -> Result<Vec<RepresentativeMark>, sqlx::Error> {
+    sqlx::query_as(
+        r#"
+        SELECT DISTINCT ON (p.category, p.gender, p.event, l.level)
+            p.category, p.gender, p.event, p.points, p.mark
+        FROM points p
+        CROSS JOIN UNNEST($2::INTEGER[]) AS l(level)
+        WHERE p.edition = $1 AND p.mark IS NOT NULL AND p.points IS NOT NULL
+        ORDER BY p.category, p.gender, p.event, l.level, ABS(p.points - l.level), p.points DESC;"#,
+    )
+    .bind(edition)
+    .bind(points)
+    .fetch_all(pool)
+    .await
+}
@@ -0,0 +1,132 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::io::Read;
+
+use sqlx::{Pool, Postgres};
+use tokio::sync::mpsc;
+
+use crate::models::import_job::{ImportError, ImportReport};
+use crate::routes::utils::data_file::{parse_rows, DataFileFormat, DataFileRow};
+
+/// The number of rows written per statement when importing a data file.
+pub const IMPORT_BATCH_SIZE: usize = 5_000;
+
+/// A data file row that can be upserted into its table on a natural key.
+pub trait ImportRow: DataFileRow {
+    /// Normalizes a row and checks it can be imported into an edition.
+    fn prepare(&mut self, edition: i32) -> Result<(), String>;
+
+    /// A hash of the row's natural key within an edition.
+    fn natural_key(&self) -> u64;
+
+    /// Decides between rows with the same natural key: a row only replaces an earlier one with a
+    /// lower rank.
+    fn rank(&self) -> i64;
+
+    /// Writes a batch of rows with distinct natural keys into an edition. Returns, for every row that
+    /// was written, whether it was inserted rather than updated. Rows already up to date are left out.
+    fn upsert(
+        pool: &Pool<Postgres>,
+        edition: i32,
+        batch: &[Self],
+    ) -> impl Future<Output = Result<Vec<bool>, sqlx::Error>> + Send;
+}
+
+/// Upserts the rows of a data file into an edition of their table.
+///
+/// The file is parsed row by row on a blocking thread while rows are validated and written in
+/// batches of [`IMPORT_BATCH_SIZE`], so memory use does not grow with the size of the file. Each
+/// batch is committed on its own, so an import that fails part way through can be re-run and only
+/// writes what is missing or changed.
+///
+/// # Arguments
+/// * `pool` - A reference to the Postgres connection pool.
+/// * `edition` - The edition the rows belong to.
+/// * `reader` - The JSON or CSV file contents.
+/// * `format` - The format of `reader`.
+/// * `progress` - Called with the running totals after every batch.
+pub async fn import_rows<T: ImportRow>(
+    pool: &Pool<Postgres>,
+    edition: i32,
+    reader: impl Read + Send + 'static,
+    format: DataFileFormat,
+    progress: impl Fn(&ImportReport),
+) -> Result<ImportReport, ImportError> {
+    let (tx, mut rx) = mpsc::channel(IMPORT_BATCH_SIZE);
+    let parser = tokio::task::spawn_blocking(move || parse_rows::<T>(reader, format, tx));
+
+    let mut report = ImportReport::default();
+    // The rank written so far for each natural key, hashed to keep memory use small.
+    let mut written: HashMap<u64, i64> = HashMap::new();
+    let mut batch: Vec<T> = Vec::with_capacity(IMPORT_BATCH_SIZE);
+    let mut batch_index: HashMap<u64, usize> = HashMap::new();
+
+    while let Some(row) = rx.recv().await {
+        let index = report.total_rows;
+        report.total_rows += 1;
+
+        let model = match row.and_then(|mut model| model.prepare(edition).map(|_| model)) {
+            Ok(model) => model,
+            Err(message) => {
+                report.add_error(index, message);
+                continue;
+            }
+        };
+
+        let key = model.natural_key();
+        if written.get(&key).is_some_and(|rank| *rank >= model.rank()) {
+            report.skipped += 1;
+            continue;
+        }
+        written.insert(key, model.rank());
+        match batch_index.get(&key) {
+            // A repeated key within the batch replaces the lower ranked row, which is then skipped.
+            Some(position) => {
+                report.skipped += 1;
+                batch[*position] = model;
+            }
+            None => {
+                batch_index.insert(key, batch.len());
+                batch.push(model);
+            }
+        }
+
+        if batch.len() == IMPORT_BATCH_SIZE {
+            write_batch(pool, edition, &batch, &mut report).await?;
+            batch.clear();
+            batch_index.clear();
+            report.processed_rows = report.total_rows;
+            progress(&report);
+        }
+    }
+
+    if !batch.is_empty() {
+        write_batch(pool, edition, &batch, &mut report).await?;
+    }
+    report.processed_rows = report.total_rows;
+    progress(&report);
+
+    parser
+        .await
+        .map_err(|e| ImportError::File(e.to_string()))?
+        .map_err(ImportError::File)?;
+
+    Ok(report)
+}
+
+/// Writes one batch of an import, adding the outcome to the report.
+async fn write_batch<T: ImportRow>(
+    pool: &Pool<Postgres>,
+    edition: i32,
+    batch: &[T],
+    report: &mut ImportReport,
+) -> Result<(), sqlx::Error> {
+    let inserted = T::upsert(pool, edition, batch).await?;
+
+    let written = inserted.len() as u64;
+    let new_rows = inserted.iter().filter(|i| **i).count() as u64;
+    report.inserted += new_rows;
+    report.updated += written - new_rows;
+    report.skipped += batch.len() as u64 - written;
+    Ok(())
+}
@@ -1,2 +1,5 @@
 pub mod users_db;
 pub mod athletics_db;
+pub mod import_db;
+pub mod age_grading_db;
+pub mod performances_db;
@@ -0,0 +1,293 @@
+use chrono::NaiveDate;
+use sqlx::{Pool, Postgres};
+
+use crate::models::iaaf_points::{Category, Gender};
+use crate::models::leaderboard::LeaderboardRow;
+use crate::models::performance::{BestRow, BestsQueryParams, PerformanceQueryParams, PerformanceRecord, PerformanceRow};
+use crate::scoring::events::resolve_event;
+
+/// Lists a user's performances, most recent first.
+///
+/// # Arguments
+/// * `pool` - A reference to the Postgres connection pool.
+/// * `user_id` - The user the performances belong to.
+/// * `params` - Optional category, event and date filters.
+pub async fn get_performances(
+    pool: &Pool<Postgres>,
+    user_id: i32,
+    params: &PerformanceQueryParams,
+) -> Result<Vec<PerformanceRow>, sqlx::Error> {
+    sqlx::query_as(
+        r#"
+        SELECT * FROM performances
+        WHERE
+            user_id = $1 AND
+            ($2::VARCHAR IS NULL OR LOWER(category) = LOWER($2)) AND
+            ($3::VARCHAR IS NULL OR LOWER(event) = LOWER($3)) AND
+            ($4::DATE IS NULL OR performance_date >= $4) AND
+            ($5::DATE IS NULL OR performance_date <= $5)
+        ORDER BY performance_date DESC NULLS LAST, id DESC;"#,
+    )
+    .bind(user_id)
+    .bind(params.category.as_ref().map(|c| c.to_string()))
+    .bind(params.event.as_deref().map(resolve_event))
+    .bind(params.from)
+    .bind(params.to)
+    .fetch_all(pool)
+    .await
+}
+
+/// Retrieves one of a user's performances.
+///
+/// # Returns
+/// `None` if the performance does not exist or belongs to another user.
+pub async fn get_performance(pool: &Pool<Postgres>, user_id: i32, id: i32) -> Result<Option<PerformanceRow>, sqlx::Error> {
+    sqlx::query_as("SELECT * FROM performances WHERE id = $1 AND user_id = $2")
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await
+}
+
+/// Records a performance for a user.
+///
+/// # Arguments
+/// * `pool` - A reference to the Postgres connection pool.
+/// * `user_id` - The user the performance belongs to.
+/// * `record` - The performance and the score it earns.
+///
+/// # Returns
+/// The new row.
+pub async fn insert_performance(
+    pool: &Pool<Postgres>,
+    user_id: i32,
+    record: &PerformanceRecord,
+) -> Result<PerformanceRow, sqlx::Error> {
+    sqlx::query_as(
+        r#"
+        INSERT INTO performances (
+            user_id, category, gender, event, mark, performance_date, competition, venue, round, place,
+            wind, timing, track, notes, edition, scored_mark, points, lower_is_better, wind_legal,
+            competition_category
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20)
+        RETURNING *;"#,
+    )
+    .bind(user_id)
+    .bind(&record.category)
+    .bind(&record.gender)
+    .bind(&record.event)
+    .bind(record.mark)
+    .bind(record.date)
+    .bind(&record.competition)
+    .bind(&record.venue)
+    .bind(&record.round)
+    .bind(record.place)
+    .bind(record.wind)
+    .bind(&record.timing)
+    .bind(&record.track)
+    .bind(&record.notes)
+    .bind(record.edition)
+    .bind(record.scored_mark)
+    .bind(record.points)
+    .bind(record.lower_is_better)
+    .bind(record.wind_legal)
+    .bind(&record.competition_category)
+    .fetch_one(pool)
+    .await
+}
+
+/// Replaces one of a user's performances, along with its score.
+///
+/// # Returns
+/// The updated row, `None` if the performance does not exist or belongs to another user.
+pub async fn update_performance(
+    pool: &Pool<Postgres>,
+    user_id: i32,
+    id: i32,
+    record: &PerformanceRecord,
+) -> Result<Option<PerformanceRow>, sqlx::Error> {
+    sqlx::query_as(
+        r#"
+        UPDATE performances
+        SET category = $3, gender = $4, event = $5, mark = $6, performance_date = $7, competition = $8,
+            venue = $9, round = $10, place = $11, wind = $12, timing = $13, track = $14, notes = $15,
+            edition = $16, scored_mark = $17, points = $18, lower_is_better = $19, wind_legal = $20,
+            competition_category = $21, date_modified = NOW()
+        WHERE id = $1 AND user_id = $2
+        RETURNING *;"#,
+    )
+    .bind(id)
+    .bind(user_id)
+    .bind(&record.category)
+    .bind(&record.gender)
+    .bind(&record.event)
+    .bind(record.mark)
+    .bind(record.date)
+    .bind(&record.competition)
+    .bind(&record.venue)
+    .bind(&record.round)
+    .bind(record.place)
+    .bind(record.wind)
+    .bind(&record.timing)
+    .bind(&record.track)
+    .bind(&record.notes)
+    .bind(record.edition)
+    .bind(record.scored_mark)
+    .bind(record.points)
+    .bind(record.lower_is_better)
+    .bind(record.wind_legal)
+    .bind(&record.competition_category)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Deletes one of a user's performances.
+///
+/// # Returns
+/// `false` if the performance does not exist or belongs to another user.
+pub async fn delete_performance(pool: &Pool<Postgres>, user_id: i32, id: i32) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query("DELETE FROM performances WHERE id = $1 AND user_id = $2")
+        .bind(id)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected() == 1)
+}
+
+/// Finds a user's personal and season bests in each event.
+///
+/// Performances are compared on their scored mark, so hand times and indoor times on non-standard
+/// tracks are compared after conversion. Wind-aided marks do not count, marks without a wind reading
+/// do. Ties go to the earlier performance. Performances without a date only count as personal bests.
+///
+/// # Arguments
+/// * `pool` - A reference to the Postgres connection pool.
+/// * `user_id` - The user the performances belong to.
+/// * `params` - Optional category and event filters.
+/// * `outdoor_season` - The first and last day of the outdoor season to find season bests in.
+/// * `indoor_season` - The first and last day of the indoor season to find season bests in.
+///
+/// # Returns
+/// The performances that are a personal best, a season best or both, ordered by category and event.
+pub async fn get_bests(
+    pool: &Pool<Postgres>,
+    user_id: i32,
+    params: &BestsQueryParams,
+    outdoor_season: (NaiveDate, NaiveDate),
+    indoor_season: (NaiveDate, NaiveDate),
+) -> Result<Vec<BestRow>, sqlx::Error> {
+    sqlx::query_as(
+        r#"
+        WITH eligible AS (
+            SELECT *,
+                COALESCE(CASE WHEN category = 'Indoor'
+                    THEN performance_date BETWEEN $6 AND $7
+                    ELSE performance_date BETWEEN $4 AND $5
+                END, FALSE) AS in_season,
+                CASE WHEN lower_is_better THEN scored_mark ELSE -scored_mark END AS sort_mark
+            FROM performances
+            WHERE
+                user_id = $1 AND
+                scored_mark IS NOT NULL AND
+                (wind_legal OR wind IS NULL) AND
+                ($2::VARCHAR IS NULL OR LOWER(category) = LOWER($2)) AND
+                ($3::VARCHAR IS NULL OR LOWER(event) = LOWER($3))
+        ),
+        ranked AS (
+            SELECT *,
+                ROW_NUMBER() OVER (
+                    PARTITION BY category, gender, event ORDER BY sort_mark, performance_date NULLS LAST, id
+                ) AS best_rank,
+                ROW_NUMBER() OVER (
+                    PARTITION BY category, gender, event, in_season ORDER BY sort_mark, performance_date, id
+                ) AS season_rank
+            FROM eligible
+        )
+        SELECT *, best_rank = 1 AS personal_best, in_season AND season_rank = 1 AS season_best
+        FROM ranked
+        WHERE best_rank = 1 OR (in_season AND season_rank = 1)
+        ORDER BY category, gender, event, best_rank;"#,
+    )
+    .bind(user_id)
+    .bind(params.category.as_ref().map(|c| c.to_string()))
+    .bind(params.event.as_deref().map(resolve_event))
+    .bind(outdoor_season.0)
+    .bind(outdoor_season.1)
+    .bind(indoor_season.0)
+    .bind(indoor_season.1)
+    .fetch_all(pool)
+    .await
+}
+
+/// Ranks athletes by their best performance, one performance per athlete.
+///
+/// An event is ranked on the scored mark, the same way personal bests are picked: wind-aided marks do
+/// not count and an athlete's tied marks go to the earlier one. Without an event, athletes are ranked
+/// on the most points they have scored in any event of the category. Equal marks or points share a
+/// rank, with the ranks after them skipped.
+///
+/// # Arguments
+/// * `pool` - A reference to the Postgres connection pool.
+/// * `category` - The indoor/outdoor category of the performances.
+/// * `gender` - The gender of the performances.
+/// * `event` - The event to rank, matched by canonical name, or `None` to rank on points.
+/// * `dates` - Only rank performances between these dates, unbounded where `None`.
+/// * `page` - The number of athletes to return and how many to skip.
+///
+/// # Returns
+/// The page of ranked athletes, each row carrying the total number ranked.
+pub async fn get_leaderboard(
+    pool: &Pool<Postgres>,
+    category: &Category,
+    gender: &Gender,
+    event: Option<&str>,
+    dates: (Option<NaiveDate>, Option<NaiveDate>),
+    page: (i64, i64),
+) -> Result<Vec<LeaderboardRow>, sqlx::Error> {
+    sqlx::query_as(
+        r#"
+        WITH eligible AS (
+            SELECT *,
+                CASE
+                    WHEN $3::VARCHAR IS NULL THEN -points
+                    WHEN lower_is_better THEN scored_mark
+                    ELSE -scored_mark
+                END AS sort_key
+            FROM performances
+            WHERE
+                LOWER(category) = LOWER($1) AND
+                LOWER(gender) = LOWER($2) AND
+                ($3::VARCHAR IS NULL OR LOWER(event) = LOWER($3)) AND
+                scored_mark IS NOT NULL AND
+                ($3::VARCHAR IS NOT NULL OR points IS NOT NULL) AND
+                (wind_legal OR wind IS NULL) AND
+                ($4::DATE IS NULL OR performance_date >= $4) AND
+                ($5::DATE IS NULL OR performance_date <= $5)
+        ),
+        athlete_bests AS (
+            SELECT DISTINCT ON (user_id) *
+            FROM eligible
+            ORDER BY user_id, sort_key, performance_date NULLS LAST, id
+        ),
+        ranked AS (
+            SELECT *, RANK() OVER (ORDER BY sort_key) AS rank, COUNT(*) OVER () AS total
+            FROM athlete_bests
+        )
+        SELECT r.*, u.first_name, u.last_name
+        FROM ranked r
+        JOIN users u ON u.id = r.user_id
+        ORDER BY r.rank, r.performance_date NULLS LAST, r.id
+        LIMIT $6 OFFSET $7;"#,
+    )
+    .bind(category.to_string())
+    .bind(gender.to_string())
+    .bind(event.map(resolve_event))
+    .bind(dates.0)
+    .bind(dates.1)
+    .bind(page.0)
+    .bind(page.1)
+    .fetch_all(pool)
+    .await
+}
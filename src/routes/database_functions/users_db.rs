@@ -1,6 +1,6 @@
-use sqlx::{query_as, Error, Pool, Postgres};
+use sqlx::{Pool, Postgres};
 
-use crate::models::{iaaf_points::PointsInsert, user::{CreateUserRequest, UpdateUserRequest, User}};
+use crate::models::user::{CreateUserRequest, UpdateUserRequest, User};
 
 
 /// Creates a new user in the database.
@@ -14,8 +14,8 @@ use crate::models::{iaaf_points::PointsInsert, user::{CreateUserRequest, UpdateU
 /// `true` if the user was successfully created, `false` otherwise.
 pub async fn create_user(pool: &Pool<Postgres>, dto: CreateUserRequest, hash: String) -> bool {
     let insert_result = sqlx::query(
-        r#"INSERT INTO users ( first_name, last_name, email, phone, active, password)
-        VALUES ($1, $2, $3, $4, TRUE, $5);
+        r#"INSERT INTO users ( first_name, last_name, email, phone, active, password, date_of_birth, gender)
+        VALUES ($1, $2, $3, $4, TRUE, $5, $6, $7);
         "#,
     )
     .bind(dto.first_name)
@@ -23,6 +23,8 @@ pub async fn create_user(pool: &Pool<Postgres>, dto: CreateUserRequest, hash: St
     .bind(dto.email)
     .bind(dto.phone)
     .bind(hash)
+    .bind(dto.date_of_birth)
+    .bind(dto.gender.map(|g| g.to_string()))
     .execute(pool)
     .await;
 
@@ -57,7 +59,8 @@ pub async fn get_user_by_username(pool: &Pool<Postgres>, email: &String) -> Opti
 ///
 /// # Arguments
 /// * `pool` - A reference to the Postgres connection pool.
-/// * `dto` - A `CreateUserRequest` struct containing the updated user information.
+/// * `dto` - A `CreateUserRequest` struct containing the updated user information. The date of
+///   birth and gender are kept when not given.
 /// * `id` - A reference to the UUID of the user to be updated.
 ///
 /// # Returns
@@ -69,14 +72,17 @@ pub async fn update_user(
 ) -> Option<User> {
     let user: Option<User> = sqlx::query_as(
         r#"UPDATE users
-        SET email = $1, first_name = $2, last_name = $3, phone = $4
-        WHERE id = $5
+        SET email = $1, first_name = $2, last_name = $3, phone = $4, date_of_birth = COALESCE($5, date_of_birth),
+            gender = COALESCE($6, gender)
+        WHERE id = $7
         RETURNING *"#,
     )
     .bind(dto.email)
     .bind(dto.first_name)
     .bind(dto.last_name)
     .bind(dto.phone)
+    .bind(dto.date_of_birth)
+    .bind(dto.gender.map(|g| g.to_string()))
     .bind(id)
     .fetch_optional(pool)
     .await
@@ -106,84 +112,3 @@ pub async fn get_user_by_id(pool: &Pool<Postgres>, id: &i32) -> Option<User> {
 
     user
 }
-
-pub async fn get_user_points(
-    pool: &Pool<Postgres>,
-    user_id: i32,
-) -> Result<Vec<PointsInsert>, Error> {
-    let user_points: Vec<PointsInsert> = query_as(
-        r#"
-        SELECT p.*
-        FROM user_points up
-        JOIN points p ON p.id = up.point_id
-        WHERE up.user_id = $1
-        "#
-    )
-    .bind(user_id)
-    .fetch_all(pool)
-    .await?;
-
-    Ok(user_points)
-}
-
-pub async fn insert_new_user_points(pool: &Pool<Postgres>, user_id: &i32, point_id: &i32) -> Result<bool, Error> {
-    //Check one does not exist
-    let user_points: Option<PointsInsert> = query_as(
-        r#"
-        SELECT *
-        FROM user_points 
-        WHERE user_id = $1 AND point_id = $2
-        "#
-    )
-    .bind(user_id)
-    .bind(point_id)
-    .fetch_optional(pool)
-    .await?;
-
-    if user_points.is_some() {
-        return Err(sqlx::Error::RowNotFound);
-    }
-
-    let insert_result = sqlx::query(
-        r#"INSERT INTO user_points (user_id, point_id) 
-        VALUES ($1, $2);
-        "#,
-    )
-    .bind(user_id)
-    .bind(point_id)
-    .execute(pool)
-    .await?;
-
-    Ok(insert_result.rows_affected() == 1)
-}
-
-
-pub async fn delete_user_points(pool: &Pool<Postgres>, user_id: &i32, point_id: &i32) -> Result<bool, Error> {
-    //Check one does not exist
-    let count: (i64,) = sqlx::query_as(
-        r#"
-        SELECT COUNT(*)
-        FROM user_points
-        WHERE user_id = $1 AND point_id = $2
-        "#
-    )
-    .bind(user_id)
-    .bind(point_id)
-    .fetch_one(pool)
-    .await?;
-
-    if count.0 == 0 {
-        return Err(sqlx::Error::RowNotFound);
-    }
-
-    let insert_result = sqlx::query(
-        r#"DELETE FROM user_points WHERE user_id = $1 AND point_id = $2;
-        "#,
-    )
-    .bind(user_id)
-    .bind(point_id)
-    .execute(pool)
-    .await?;
-
-    Ok(insert_result.rows_affected() == 1)
-}
@@ -0,0 +1,100 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Query, State},
+    http::{header::CONTENT_DISPOSITION, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+
+use crate::{
+    models::iaaf_points::{EditionDiffQueryParams, MarkQuery},
+    scoring::{
+        edition_diff::{diff, SampleScore, REPRESENTATIVE_POINTS},
+        timing::TimingMethod,
+        track::TrackType,
+    },
+};
+
+use super::{
+    database_functions::athletics_db::{get_editions, get_event_catalog, get_points_for_marks, get_representative_marks},
+    routes::AppState,
+};
+
+/// Compares two loaded editions of the scoring tables.
+///
+/// The marks closest to a set of representative points values in the `from` edition are scored under
+/// both editions, giving the score change per event along with the events added and removed.
+pub async fn get_edition_diff_handler(
+    Query(params): Query<EditionDiffQueryParams>,
+    State(data): State<Arc<AppState>>,
+) -> Result<Response, (StatusCode, Json<serde_json::Value>)> {
+    let database_error = |e: sqlx::Error| {
+        let error_response = serde_json::json!({
+            "status": "error",
+            "message": format!("Database error: { }", e),
+        });
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    };
+
+    let editions = get_editions(&data.db).await.map_err(database_error)?;
+    for edition in [params.from, params.to] {
+        if !editions.iter().any(|e| e.edition == edition) {
+            let error_response = serde_json::json!({
+                "status": "error",
+                "message": format!("Edition {} has not been loaded", edition),
+            });
+            return Err((StatusCode::NOT_FOUND, Json(error_response)));
+        }
+    }
+
+    let from_events = get_event_catalog(&data.db, None, None, Some(params.from)).await.map_err(database_error)?;
+    let to_events = get_event_catalog(&data.db, None, None, Some(params.to)).await.map_err(database_error)?;
+    let marks = get_representative_marks(&data.db, params.from, &REPRESENTATIVE_POINTS)
+        .await
+        .map_err(database_error)?;
+
+    // Score every mark under both editions in one batch: the `from` query followed by the `to` query.
+    let queries: Vec<MarkQuery> = marks
+        .iter()
+        .filter_map(|mark| {
+            Some(MarkQuery {
+                category: mark.category.parse().ok()?,
+                gender: mark.gender.parse().ok()?,
+                event: mark.event.clone(),
+                mark: mark.mark,
+                edition: Some(params.from),
+                timing: TimingMethod::Fat,
+                wind: None,
+                track: TrackType::Banked,
+            })
+        })
+        .flat_map(|query| [query.clone(), MarkQuery { edition: Some(params.to), ..query }])
+        .collect();
+    let scores = get_points_for_marks(&data.db, &queries).await.map_err(database_error)?;
+
+    let samples: Vec<SampleScore> = queries
+        .chunks(2)
+        .zip(scores.chunks(2))
+        .filter_map(|(query, scores)| {
+            let from_score = scores[0].as_ref()?;
+            Some(SampleScore {
+                category: query[0].category.to_string(),
+                gender: query[0].gender.to_string(),
+                event: query[0].event.clone(),
+                mark: from_score.mark,
+                lower_is_better: from_score.lower_is_better,
+                from_points: from_score.points.as_ref().map(|p| p.points),
+                to_points: scores[1].as_ref().and_then(|s| s.points.as_ref()).map(|p| p.points),
+            })
+        })
+        .collect();
+
+    let report = diff(params.from, params.to, &from_events, &to_events, samples);
+
+    if params.download {
+        let disposition = format!("attachment; filename=\"edition_diff_{}_{}.json\"", params.from, params.to);
+        return Ok(([(CONTENT_DISPOSITION, disposition)], Json(report)).into_response());
+    }
+    Ok(Json(report).into_response())
+}
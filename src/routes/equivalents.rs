@@ -0,0 +1,93 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+
+use crate::{
+    models::iaaf_points::{Category, EquivalentsQueryParams, Gender, MarkQuery, MarkRange},
+    scoring::{events::resolve_event, marks::parse_scorable_mark},
+};
+
+use super::{
+    database_functions::athletics_db::{get_equivalent_marks, get_points_for_mark},
+    routes::AppState,
+};
+
+/// Scores a mark and returns the marks worth the same points in the other events of its category
+/// and gender, e.g. what a 21.98 200m is worth over 100m and 400m.
+pub async fn get_equivalents_handler(
+    Path((category, gender, event)): Path<(Category, Gender, String)>,
+    Query(params): Query<EquivalentsQueryParams>,
+    State(data): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let mark = params.mark.as_deref().map(parse_scorable_mark).transpose().map_err(|e| {
+        let error_response = serde_json::json!({
+            "status": "Bad Request",
+            "message": format!("Invalid mark: {}", e),
+        });
+        (StatusCode::BAD_REQUEST, Json(error_response))
+    })?;
+    let Some(mark) = mark else {
+        let bad_json = serde_json::json!({
+            "status": "Bad Request",
+            "message": "The mark query parameter is required",
+        });
+        return Err((StatusCode::BAD_REQUEST, Json(bad_json)));
+    };
+
+    let database_error = |e: sqlx::Error| {
+        let error_response = serde_json::json!({
+            "status": "error",
+            "message": format!("Database error: { }", e),
+        });
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    };
+
+    let query = MarkQuery {
+        category: category.clone(),
+        gender: gender.clone(),
+        event: event.clone(),
+        mark,
+        edition: params.edition,
+        timing: params.timing.unwrap_or_default(),
+        wind: None,
+        track: params.track.unwrap_or_default(),
+    };
+    let Some(source) = get_points_for_mark(&data.db, query).await.map_err(database_error)? else {
+        let error_response = serde_json::json!({
+            "status": "error",
+            "message": format!("No points table found for {} {} {}", category, gender, event),
+        });
+        return Err((StatusCode::NOT_FOUND, Json(error_response)));
+    };
+    let Some(points) = source.points.as_ref().map(|p| p.points) else {
+        let error_response = serde_json::json!({
+            "status": "Bad Request",
+            "message": format!("{} is below the lowest mark in the {} table", source.mark_display, source.event),
+        });
+        return Err((StatusCode::UNPROCESSABLE_ENTITY, Json(error_response)));
+    };
+
+    let events: Option<Vec<String>> = params
+        .events
+        .map(|events| events.split(',').map(str::trim).filter(|e| !e.is_empty()).map(str::to_string).collect());
+    let source_event = resolve_event(&event);
+    let equivalents: Vec<MarkRange> =
+        get_equivalent_marks(&data.db, &category, &gender, points, params.edition, events.as_deref())
+            .await
+            .map_err(database_error)?
+            .into_iter()
+            .filter(|range| !range.points.event.eq_ignore_ascii_case(&source_event))
+            .collect();
+
+    let json_response = serde_json::json!({
+        "points": points,
+        "source": source,
+        "equivalents": equivalents,
+    });
+    Ok(Json(json_response))
+}
@@ -1,123 +1,306 @@
-use crate::models::iaaf_points::{Category, Gender, PointsInsert, PointsSearchQueryParams};
+use crate::models::age_grading::DEFAULT_AGE_FACTOR_EDITION;
+use crate::models::import_job::{ImportJob, ImportReport, ImportStatus, ImportTable};
+use crate::models::iaaf_points::{Category, EventCatalogQueryParams, Gender, ImportQueryParams, MarkQuery, PointsSearchQueryParams, DEFAULT_EDITION};
+use crate::scoring::{engine::ScoringEngine, marks::parse_scorable_mark};
 use axum::{
-    extract::{Path, Query, State}, http::StatusCode, response::IntoResponse, Extension, Json
+    extract::{Multipart, Path, Query, State}, http::StatusCode, response::IntoResponse, Json
 };
 use serde_json;
-use std::sync::Arc;
+use std::{path::PathBuf, sync::{Arc, Mutex}};
+use tokio::io::AsyncWriteExt;
+use uuid::Uuid;
 
-use super::{database_functions::users_db::{delete_user_points, get_user_points, insert_new_user_points},
-            jwt_auth::JWTAuthMiddleware, routes::AppState, database_functions::athletics_db::{read_into_db}};
+use super::{routes::AppState, database_functions::athletics_db::{get_all_points, get_editions, get_event_catalog, get_marks_for_points, get_points_for_mark, import_file, read_into_db},
+            database_functions::age_grading_db::{import_age_factor_file, read_age_factors_into_db},
+            utils::{data_file::DataFileFormat, lookup_cache::{MarkLookupKey, PointsLookupKey}}};
 
 
+/// Where an import job reads its rows from.
+enum ImportSource {
+    /// The edition's data file in `data/`.
+    DataFile,
+    /// An uploaded file saved to a temporary path, removed once the import finishes.
+    Upload(PathBuf, DataFileFormat),
+}
+
+/// Starts importing a scoring table or age factor edition from its data file in the background. The
+/// import is tracked as a job whose progress can be polled through [`get_import_job_handler`].
 pub async fn read_iaaf_json(
+    Query(params): Query<ImportQueryParams>,
     State(data): State<Arc<AppState>>,
 ) -> Result<impl IntoResponse, (axum::http::StatusCode, Json<serde_json::Value>)> {
-        let json_response = serde_json::json!({
-            "Status" : "Values Added!",
-        });
-
-        match read_into_db(&data.db).await{
-            true => Ok(Json(json_response)),
-            false => Err((StatusCode::BAD_REQUEST, Json(json_response)))
-        }
+    start_import_job(&data, params.table, import_edition(&params), ImportSource::DataFile).await
 }
 
+/// The edition an import writes to, the table's default edition when not given.
+fn import_edition(params: &ImportQueryParams) -> i32 {
+    params.edition.unwrap_or(match params.table {
+        ImportTable::Points => DEFAULT_EDITION,
+        ImportTable::AgeFactors => DEFAULT_AGE_FACTOR_EDITION,
+    })
+}
 
-
-pub async fn get_value(
-    Path((category, gender, event)): Path<(Category, Gender, String)>,
-    Query(params): Query<PointsSearchQueryParams>,
+/// Imports an uploaded JSON or CSV scoring table or age factor file as a tracked job, like [`read_iaaf_json`]. The
+/// first field of the multipart form is the file. Its format comes from the field's content type,
+/// falling back to the file name.
+pub async fn upload_points_handler(
+    Query(params): Query<ImportQueryParams>,
     State(data): State<Arc<AppState>>,
+    mut multipart: Multipart,
 ) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
-    if (params.mark.is_some() && params.points.is_some())
-        || (params.mark.is_none() && params.points.is_none())
-    {
-        let bad_json = serde_json::json!({
-            "status": "Bad Request"
+    let bad_request = |message: String| {
+        let error_response = serde_json::json!({
+            "status": "Bad Request",
+            "message": message,
         });
-        return Err((StatusCode::NOT_FOUND, Json(bad_json)));
+        (StatusCode::BAD_REQUEST, Json(error_response))
+    };
+
+    let Some(mut field) = multipart.next_field().await.map_err(|e| bad_request(e.to_string()))? else {
+        return Err(bad_request("No file was uploaded".to_string()));
+    };
+    if field.file_name().is_none() {
+        return Err(bad_request("The first form field must be a file".to_string()));
+    }
+    let format = field
+        .content_type()
+        .and_then(DataFileFormat::from_content_type)
+        .unwrap_or_else(|| DataFileFormat::from_file_name(field.file_name().unwrap_or_default()));
+
+    // Spool the upload to disk so large tables are never held in memory.
+    let path = std::env::temp_dir().join(format!("points_upload_{}", Uuid::new_v4()));
+    let saved = async {
+        let mut file = tokio::fs::File::create(&path).await.map_err(|e| e.to_string())?;
+        while let Some(chunk) = field.chunk().await.map_err(|e| e.to_string())? {
+            file.write_all(&chunk).await.map_err(|e| e.to_string())?;
+        }
+        file.flush().await.map_err(|e| e.to_string())
+    }
+    .await;
+    if let Err(e) = saved {
+        let _ = tokio::fs::remove_file(&path).await;
+        return Err(bad_request(format!("Could not read the upload: {}", e)));
+    }
+
+    let source = ImportSource::Upload(path.clone(), format);
+    let response = start_import_job(&data, params.table, import_edition(&params), source).await;
+    if response.is_err() {
+        let _ = tokio::fs::remove_file(&path).await;
     }
+    response
+}
 
-    let query_result = sqlx::query_as::<_, PointsInsert>(
-        r#"
-    SELECT * FROM points 
-    WHERE 
-        LOWER(category) = LOWER($1) AND 
-        LOWER(gender) = LOWER($2) AND 
-        LOWER(event) = LOWER($3) AND
-        (ROUND(mark::numeric, 2) = $4 OR points = $5)
-    ORDER BY CASE 
-        WHEN mark IS NULL THEN 1 
-        ELSE ABS($4 - 1400) 
-    END
-    FETCH FIRST 1 ROWS ONLY;"#,
-    )
-    .bind(category.to_string())
-    .bind(gender.to_string())
-    .bind(event)
-    .bind(params.mark)
-    .bind(params.points)
-    .fetch_optional(&data.db)
-    .await
-    .map_err(|e| {
+async fn start_import_job(
+    data: &Arc<AppState>,
+    table: ImportTable,
+    edition: i32,
+    source: ImportSource,
+) -> Result<(StatusCode, Json<serde_json::Value>), (StatusCode, Json<serde_json::Value>)> {
+    // Claiming the edition checks and marks it as running in one step.
+    if !data.running_imports.lock().unwrap().insert((table, edition)) {
         let error_response = serde_json::json!({
             "status": "error",
-            "message": format!("Database error: { }", e),
+            "message": format!("The {} edition {} is already being imported", table, edition),
         });
-        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
-    })?;
+        return Err((StatusCode::CONFLICT, Json(error_response)));
+    }
 
+    let job = ImportJob::new(table, edition);
     let json_response = serde_json::json!({
-        "points": query_result
+        "status": "success",
+        "status_url": format!("/api/world_aths/read/{}", job.id),
+        "job": job,
     });
+    let job_id = job.id;
+    let job = Arc::new(Mutex::new(job));
+    data.import_jobs.insert(job_id, job.clone()).await;
+    tokio::spawn(run_import_job(data.clone(), job, source));
 
-    Ok(Json(json_response))
+    Ok((StatusCode::ACCEPTED, Json(json_response)))
 }
 
-pub async fn get_user_points_handler(
-    Path(user_id): Path<i32>,
-    State(data): State<Arc<AppState>>
-) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)>{
-    if  user_id == 0 {
-        let bad_json = serde_json::json!({
-            "status": "Bad Request"
-        });
-        return Err((StatusCode::NOT_FOUND, Json(bad_json)));
+async fn run_import_job(data: Arc<AppState>, job: Arc<Mutex<ImportJob>>, source: ImportSource) {
+    let (table, edition) = {
+        let job = job.lock().unwrap();
+        (job.table, job.edition)
+    };
+    let progress = |report: &ImportReport| job.lock().unwrap().report = report.clone();
+    let result = match (&source, table) {
+        (ImportSource::DataFile, ImportTable::Points) => read_into_db(&data.db, edition, progress).await,
+        (ImportSource::DataFile, ImportTable::AgeFactors) => read_age_factors_into_db(&data.db, edition, progress).await,
+        (ImportSource::Upload(path, format), _) => {
+            let result = match table {
+                ImportTable::Points => import_file(&data.db, edition, path, *format, progress).await,
+                ImportTable::AgeFactors => import_age_factor_file(&data.db, edition, path, *format, progress).await,
+            };
+            let _ = tokio::fs::remove_file(path).await;
+            result
+        }
+    };
+
+    if table == ImportTable::Points {
+        // Even a failed import may have written some batches.
+        data.lookups.invalidate();
+    }
+    if result.is_ok() && table == ImportTable::Points {
+        // Keep the scoring engine in step with the latest table.
+        if let Ok(rows) = get_all_points(&data.db, None).await {
+            *data.engine.write().unwrap() = ScoringEngine::fit(&rows);
+        }
     }
 
-    match get_user_points(&data.db, user_id).await{
-        Ok(points) => {
-            let json_response = serde_json::json!({
-                "user_points": points
-            });
-            Ok(Json(json_response))
+    let mut job = job.lock().unwrap();
+    match result {
+        Ok(report) => {
+            job.report = report;
+            job.status = ImportStatus::Completed;
         }
         Err(e) => {
+            job.status = ImportStatus::Failed;
+            job.error = Some(e.to_string());
+        }
+    }
+    data.running_imports.lock().unwrap().remove(&(table, edition));
+}
+
+/// Reports the progress of an import started through [`read_iaaf_json`].
+pub async fn get_import_job_handler(
+    Path(job_id): Path<Uuid>,
+    State(data): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    match data.import_jobs.get(&job_id).await {
+        Some(job) => {
+            let job = job.lock().unwrap().clone();
+            Ok(Json(serde_json::json!(job)))
+        }
+        None => {
+            let error_response = serde_json::json!({
+                "status": "error",
+                "message": format!("No import job found with id {}", job_id),
+            });
+            Err((StatusCode::NOT_FOUND, Json(error_response)))
+        }
+    }
+}
+
+
+
+pub async fn get_value(
+    Path((category, gender, event)): Path<(Category, Gender, String)>,
+    Query(params): Query<PointsSearchQueryParams>,
+    State(data): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let mark = match (params.mark, params.points) {
+        (Some(mark), None) => parse_scorable_mark(&mark).map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "Bad Request",
+                "message": format!("Invalid mark: {}", e),
+            });
+            (StatusCode::BAD_REQUEST, Json(error_response))
+        })?,
+        (None, Some(points)) => return marks_for_points_response(&data, category, gender, event, points, params.edition).await,
+        _ => {
+            let bad_json = serde_json::json!({
+                "status": "Bad Request"
+            });
+            return Err((StatusCode::NOT_FOUND, Json(bad_json)));
+        }
+    };
+
+    let query = MarkQuery {
+        category: category.clone(),
+        gender: gender.clone(),
+        event: event.clone(),
+        mark,
+        edition: params.edition,
+        timing: params.timing.unwrap_or_default(),
+        wind: params.wind,
+        track: params.track.unwrap_or_default(),
+    };
+    let lookups = &data.lookups;
+    let score = lookups
+        .get_or_fetch(&lookups.scores, MarkLookupKey::from(&query), get_points_for_mark(&data.db, query))
+        .await
+        .map_err(|e| {
             let error_response = serde_json::json!({
                 "status": "error",
                 "message": format!("Database error: { }", e),
             });
-            Err((StatusCode::INTERNAL_SERVER_ERROR, Json(error_response)))
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    match score {
+        Some(score) => Ok(Json(serde_json::json!(score))),
+        None => {
+            let error_response = serde_json::json!({
+                "status": "error",
+                "message": format!("No points table found for {} {} {}", category, gender, event),
+            });
+            Err((StatusCode::NOT_FOUND, Json(error_response)))
         }
     }
 }
 
-pub async fn add_user_points_handler(
-    Path((user_id, point_id)): Path<(i32, i32)>,
+/// Reverse lookup: returns the range of marks that earn a points value in an event, falling back to
+/// the closest achievable points value when the requested one does not exist.
+pub async fn get_marks_for_points_handler(
+    Path((category, gender, event)): Path<(Category, Gender, String)>,
+    Query(params): Query<PointsSearchQueryParams>,
     State(data): State<Arc<AppState>>,
-    Extension(jwtauth): Extension<JWTAuthMiddleware>,
-) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)>{
-    if  jwtauth.user.id != user_id {
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let Some(points) = params.points else {
         let bad_json = serde_json::json!({
-            "status": "Bad Request"
+            "status": "Bad Request",
+            "message": "The points query parameter is required",
         });
-        return Err((StatusCode::NOT_FOUND, Json(bad_json)));
+        return Err((StatusCode::BAD_REQUEST, Json(bad_json)));
+    };
+
+    marks_for_points_response(&data, category, gender, event, points, params.edition).await
+}
+
+async fn marks_for_points_response(
+    data: &Arc<AppState>,
+    category: Category,
+    gender: Gender,
+    event: String,
+    points: i32,
+    edition: Option<i32>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    let key = PointsLookupKey::new(category.to_string(), gender.to_string(), &event, points, edition);
+    let lookups = &data.lookups;
+    let range = lookups
+        .get_or_fetch(&lookups.ranges, key, get_marks_for_points(&data.db, &category, &gender, &event, points, edition))
+        .await
+        .map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "error",
+                "message": format!("Database error: { }", e),
+            });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    match range {
+        Some(range) => Ok(Json(serde_json::json!(range))),
+        None => {
+            let error_response = serde_json::json!({
+                "status": "error",
+                "message": format!("No points table found for {} {} {}", category, gender, event),
+            });
+            Err((StatusCode::NOT_FOUND, Json(error_response)))
+        }
     }
+}
 
-    match insert_new_user_points(&data.db,&user_id, &point_id).await{
-        Ok(_) => {
+/// Lists the events available in the scoring tables, optionally filtered by category and gender.
+pub async fn get_event_catalog_handler(
+    Query(params): Query<EventCatalogQueryParams>,
+    State(data): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    match get_event_catalog(&data.db, params.category.as_ref(), params.gender.as_ref(), params.edition).await {
+        Ok(events) => {
             let json_response = serde_json::json!({
-                "user_points": "success"
+                "events": events
             });
             Ok(Json(json_response))
         }
@@ -131,32 +314,36 @@ pub async fn add_user_points_handler(
     }
 }
 
-pub async fn delete_user_points_handler(
-    Path((user_id, point_id)): Path<(i32, i32)>,
+/// Reports the hit and miss counts of the points lookup cache.
+pub async fn get_lookup_cache_stats_handler(
     State(data): State<Arc<AppState>>,
-    Extension(jwtauth): Extension<JWTAuthMiddleware>,
-) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)>{
-    if  jwtauth.user.id != user_id {
-        let bad_json = serde_json::json!({
-            "status": "Bad Request"
-        });
-        return Err((StatusCode::NOT_FOUND, Json(bad_json)));
-    }
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let json_response = serde_json::json!({
+        "scores": data.lookups.scores.stats(),
+        "ranges": data.lookups.ranges.stats(),
+        "invalidations": data.lookups.invalidations(),
+    });
+    Ok(Json(json_response))
+}
 
-    match delete_user_points(&data.db,&user_id, &point_id).await{
-        Ok(_) => {
+/// Lists the scoring table editions that have been loaded, newest first.
+pub async fn get_editions_handler(
+    State(data): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    match get_editions(&data.db).await {
+        Ok(editions) => {
             let json_response = serde_json::json!({
-                "user_points": "success"
+                "latest": editions.first().map(|e| e.edition),
+                "editions": editions
             });
-            return Ok(Json(json_response));
+            Ok(Json(json_response))
         }
         Err(e) => {
             let error_response = serde_json::json!({
                 "status": "error",
                 "message": format!("Database error: { }", e),
             });
-            return Err((StatusCode::INTERNAL_SERVER_ERROR, Json(error_response)));
+            Err((StatusCode::INTERNAL_SERVER_ERROR, Json(error_response)))
         }
     }
 }
-
@@ -139,3 +139,26 @@ pub async fn auth(
     });
     Ok(next.run(req).await)
 }
+
+/// Only lets administrators through, the users whose emails are listed in the config.
+///
+/// Must run after [`auth`], which puts the logged in user on the request.
+pub async fn admin(
+    State(data): State<Arc<AppState>>,
+    req: Request<Body>,
+    next: Next,
+) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    let is_admin = req
+        .extensions()
+        .get::<JWTAuthMiddleware>()
+        .is_some_and(|jwtauth| data.env.admin_emails.contains(&jwtauth.user.email.to_lowercase()));
+
+    if !is_admin {
+        let error_response = ErrorResponse {
+            status: RESPONSE_STATUS_FAIL,
+            message: NOT_AN_ADMIN.to_string(),
+        };
+        return Err((StatusCode::FORBIDDEN, Json(error_response)));
+    }
+    Ok(next.run(req).await)
+}
@@ -0,0 +1,115 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+
+use crate::{
+    models::{
+        iaaf_points::{Category, Gender},
+        leaderboard::{LeaderboardEntry, LeaderboardQueryParams},
+    },
+    scoring::events::resolve_event,
+};
+
+use super::{database_functions::performances_db::get_leaderboard, routes::AppState};
+
+/// The athletes returned per page when the request does not say.
+const DEFAULT_PAGE_SIZE: i64 = 50;
+/// The most athletes returned in one page.
+const MAX_PAGE_SIZE: i64 = 200;
+
+/// Ranks athletes by their best recorded performance in an event.
+pub async fn get_event_leaderboard_handler(
+    Path((category, gender, event)): Path<(Category, Gender, String)>,
+    Query(params): Query<LeaderboardQueryParams>,
+    State(data): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    leaderboard_response(&data, category, gender, Some(&event), &params).await
+}
+
+/// Ranks athletes by the most points they have scored in any event of a category.
+pub async fn get_points_leaderboard_handler(
+    Path((category, gender)): Path<(Category, Gender)>,
+    Query(params): Query<LeaderboardQueryParams>,
+    State(data): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    leaderboard_response(&data, category, gender, None, &params).await
+}
+
+async fn leaderboard_response(
+    data: &Arc<AppState>,
+    category: Category,
+    gender: Gender,
+    event: Option<&str>,
+    params: &LeaderboardQueryParams,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    let page = params.page.unwrap_or(1);
+    let page_size = params.page_size.unwrap_or(DEFAULT_PAGE_SIZE);
+    let bad_request = |message: String| {
+        let error_response = serde_json::json!({
+            "status": "Bad Request",
+            "message": message,
+        });
+        (StatusCode::BAD_REQUEST, Json(error_response))
+    };
+    if page < 1 || !(1..=MAX_PAGE_SIZE).contains(&page_size) {
+        return Err(bad_request(format!(
+            "The page must be at least 1 and the page size between 1 and {}",
+            MAX_PAGE_SIZE
+        )));
+    }
+    let offset = (page - 1)
+        .checked_mul(page_size)
+        .ok_or_else(|| bad_request(format!("Page {} is out of range", page)))?;
+
+    // A season narrows the date range further.
+    let (mut from, mut to) = (params.from, params.to);
+    if let Some(season) = params.season {
+        let (start, end) = data.env.seasons.for_category(&category)
+            .dates(season)
+            .ok_or_else(|| bad_request(format!("Season {} is out of range", season)))?;
+        from = Some(from.map_or(start, |from| from.max(start)));
+        to = Some(to.map_or(end, |to| to.min(end)));
+    }
+
+    let database_error = |e: sqlx::Error| {
+        let error_response = serde_json::json!({
+            "status": "error",
+            "message": format!("Database error: { }", e),
+        });
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    };
+    let rows = get_leaderboard(&data.db, &category, &gender, event, (from, to), (page_size, offset))
+        .await
+        .map_err(database_error)?;
+    let total = match rows.first() {
+        Some(row) => row.total,
+        // Past the last page: count the athletes from the first one.
+        None if offset > 0 => get_leaderboard(&data.db, &category, &gender, event, (from, to), (1, 0))
+            .await
+            .map_err(database_error)?
+            .first()
+            .map_or(0, |row| row.total),
+        None => 0,
+    };
+    let entries: Vec<LeaderboardEntry> = rows.into_iter().map(LeaderboardEntry::from).collect();
+
+    let json_response = serde_json::json!({
+        "category": category,
+        "gender": gender,
+        "event": event.map(resolve_event),
+        "ranked_by": if event.is_some() { "mark" } else { "points" },
+        "season": params.season,
+        "from": from,
+        "to": to,
+        "page": page,
+        "page_size": page_size,
+        "total": total,
+        "entries": entries,
+    });
+    Ok(Json(json_response))
+}
@@ -4,7 +4,17 @@ mod health_check;
 mod iaaf_points;
 mod jwt_auth;
 mod system_info;
-mod utils;
+pub mod utils;
 mod auth;
 pub(crate) mod database_functions;
-mod files;
\ No newline at end of file
+mod files;
+mod combined_events;
+mod scoring_engine;
+mod bulk_scoring;
+mod edition_diff;
+mod equivalents;
+mod age_grading;
+mod scoring_curves;
+mod performances;
+mod leaderboards;
+mod world_rankings;
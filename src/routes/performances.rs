@@ -0,0 +1,291 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Extension, Json,
+};
+
+use crate::{
+    models::{
+        iaaf_points::{Category, Gender, MarkQuery},
+        performance::{
+            BestsQueryParams, EventBests, Performance, PerformanceQueryParams, PerformanceRecord, PerformanceRequest,
+            ProgressionQueryParams,
+        },
+        user::User,
+    },
+    scoring::{marks::check_mark, progression::progression, timing::TimingMethod, track::TrackType},
+};
+
+use super::{
+    database_functions::{
+        athletics_db::{get_points_for_mark, get_points_for_marks},
+        performances_db::{delete_performance, get_bests, get_performance, get_performances, insert_performance, update_performance},
+    },
+    jwt_auth::JWTAuthMiddleware,
+    routes::AppState,
+};
+
+/// Lists the logged in user's performances, most recent first.
+pub async fn get_performances_handler(
+    Extension(jwtauth): Extension<JWTAuthMiddleware>,
+    Query(params): Query<PerformanceQueryParams>,
+    State(data): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let performances: Vec<Performance> = get_performances(&data.db, jwtauth.user.id, &params)
+        .await
+        .map_err(database_error)?
+        .into_iter()
+        .map(Performance::from)
+        .collect();
+
+    let json_response = serde_json::json!({
+        "performances": performances,
+    });
+    Ok(Json(json_response))
+}
+
+pub async fn get_performance_handler(
+    Extension(jwtauth): Extension<JWTAuthMiddleware>,
+    Path(id): Path<i32>,
+    State(data): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    match get_performance(&data.db, jwtauth.user.id, id).await.map_err(database_error)? {
+        Some(row) => Ok(Json(Performance::from(row))),
+        None => Err(not_found(id)),
+    }
+}
+
+/// Records a performance for the logged in user, scoring it against the points tables.
+pub async fn create_performance_handler(
+    Extension(jwtauth): Extension<JWTAuthMiddleware>,
+    State(data): State<Arc<AppState>>,
+    Json(request): Json<PerformanceRequest>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let record = score_performance(&data, &jwtauth.user, request).await?;
+    let row = insert_performance(&data.db, jwtauth.user.id, &record).await.map_err(database_error)?;
+
+    Ok((StatusCode::CREATED, Json(Performance::from(row))))
+}
+
+/// Replaces one of the logged in user's performances and scores it again.
+pub async fn update_performance_handler(
+    Extension(jwtauth): Extension<JWTAuthMiddleware>,
+    Path(id): Path<i32>,
+    State(data): State<Arc<AppState>>,
+    Json(request): Json<PerformanceRequest>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let record = score_performance(&data, &jwtauth.user, request).await?;
+    match update_performance(&data.db, jwtauth.user.id, id, &record).await.map_err(database_error)? {
+        Some(row) => Ok(Json(Performance::from(row))),
+        None => Err(not_found(id)),
+    }
+}
+
+pub async fn delete_performance_handler(
+    Extension(jwtauth): Extension<JWTAuthMiddleware>,
+    Path(id): Path<i32>,
+    State(data): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    if !delete_performance(&data.db, jwtauth.user.id, id).await.map_err(database_error)? {
+        return Err(not_found(id));
+    }
+
+    let json_response = serde_json::json!({
+        "status": "success",
+    });
+    Ok(Json(json_response))
+}
+
+/// Gives the logged in user's personal best and season best in every event they have a performance
+/// in. Season boundaries come from the indoor and outdoor season starts in the config.
+pub async fn get_bests_handler(
+    Extension(jwtauth): Extension<JWTAuthMiddleware>,
+    Query(params): Query<BestsQueryParams>,
+    State(data): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let today = chrono::Local::now().date_naive();
+    let season = |category: &Category| {
+        let start = data.env.seasons.for_category(category);
+        params.season.unwrap_or_else(|| start.season_of(today))
+    };
+    let (outdoor, indoor) = (season(&Category::Outdoor), season(&Category::Indoor));
+    let season_dates = (
+        data.env.seasons.outdoor.dates(outdoor).ok_or_else(|| season_out_of_range(outdoor))?,
+        data.env.seasons.indoor.dates(indoor).ok_or_else(|| season_out_of_range(indoor))?,
+    );
+
+    let rows = get_bests(&data.db, jwtauth.user.id, &params, season_dates.0, season_dates.1)
+        .await
+        .map_err(database_error)?;
+
+    // Rows come grouped by event with the personal best first.
+    let mut bests: Vec<EventBests> = vec![];
+    for row in rows {
+        let (personal_best, season_best) = (row.personal_best, row.season_best);
+        let performance = Performance::from(row.performance);
+        if personal_best {
+            bests.push(EventBests {
+                category: performance.category.clone(),
+                gender: performance.gender.clone(),
+                event: performance.event.clone(),
+                season: if performance.category == Category::Indoor.to_string() { indoor } else { outdoor },
+                season_best: season_best.then(|| performance.clone()),
+                personal_best: performance,
+            });
+        } else if let Some(event) = bests.last_mut() {
+            event.season_best = Some(performance);
+        }
+    }
+
+    let json_response = serde_json::json!({
+        "seasons": {
+            "outdoor": outdoor,
+            "indoor": indoor,
+        },
+        "bests": bests,
+    });
+    Ok(Json(json_response))
+}
+
+/// Charts how the logged in user's performances progress over time, one series per event.
+///
+/// Points are the ones each performance scored when it was recorded, unless an `edition` is given,
+/// in which case every scored mark is scored again against that edition of the tables.
+pub async fn get_progression_handler(
+    Extension(jwtauth): Extension<JWTAuthMiddleware>,
+    Query(params): Query<ProgressionQueryParams>,
+    State(data): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let mut performances: Vec<Performance> =
+        get_performances(&data.db, jwtauth.user.id, &PerformanceQueryParams::from(&params))
+            .await
+            .map_err(database_error)?
+            .into_iter()
+            .map(Performance::from)
+            .collect();
+
+    if let Some(edition) = params.edition {
+        // Scored marks already have hand times and track conversions applied.
+        let queries: Vec<MarkQuery> = performances
+            .iter()
+            .map(|p| MarkQuery {
+                category: p.category.parse().unwrap_or(Category::Outdoor),
+                gender: p.gender.parse().unwrap_or(Gender::Male),
+                event: p.event.clone(),
+                mark: p.scored_mark.unwrap_or(p.mark),
+                edition: Some(edition),
+                timing: TimingMethod::Fat,
+                wind: None,
+                track: TrackType::Banked,
+            })
+            .collect();
+        let scores = get_points_for_marks(&data.db, &queries).await.map_err(database_error)?;
+        for (performance, score) in performances.iter_mut().zip(scores) {
+            performance.edition = score.as_ref().map(|s| s.edition);
+            performance.points = score.and_then(|s| s.points).map(|p| p.points);
+        }
+    }
+
+    let json_response = serde_json::json!({
+        "aggregate": params.aggregate,
+        "edition": params.edition,
+        "events": progression(performances, params.aggregate, &data.env.seasons),
+    });
+    Ok(Json(json_response))
+}
+
+/// Scores a performance the way the points lookup does, with its timing method, wind and track, and
+/// stores it under the event's canonical name.
+async fn score_performance(
+    data: &Arc<AppState>,
+    user: &User,
+    request: PerformanceRequest,
+) -> Result<PerformanceRecord, (StatusCode, Json<serde_json::Value>)> {
+    let bad_request = |message: String| {
+        let error_response = serde_json::json!({
+            "status": "Bad Request",
+            "message": message,
+        });
+        (StatusCode::BAD_REQUEST, Json(error_response))
+    };
+
+    let gender = match request.gender {
+        Some(gender) => gender,
+        None => user
+            .gender
+            .as_deref()
+            .and_then(|g| g.parse::<Gender>().ok())
+            .ok_or_else(|| bad_request("Give a gender or add one to your profile".to_string()))?,
+    };
+    check_mark(request.mark).map_err(|e| bad_request(format!("Invalid mark: {}", e)))?;
+    if request.place.is_some_and(|place| place < 1) {
+        return Err(bad_request("The place must be at least 1".to_string()));
+    }
+
+    let query = MarkQuery {
+        category: request.category.clone(),
+        gender: gender.clone(),
+        event: request.event.clone(),
+        mark: request.mark,
+        edition: request.edition,
+        timing: request.timing,
+        wind: request.wind,
+        track: request.track,
+    };
+    let Some(score) = get_points_for_mark(&data.db, query).await.map_err(database_error)? else {
+        let error_response = serde_json::json!({
+            "status": "error",
+            "message": format!("No points table found for {} {} {}", request.category, gender, request.event),
+        });
+        return Err((StatusCode::NOT_FOUND, Json(error_response)));
+    };
+
+    Ok(PerformanceRecord {
+        category: request.category.to_string(),
+        gender: gender.to_string(),
+        event: score.event,
+        mark: request.mark,
+        date: request.date,
+        competition: request.competition,
+        venue: request.venue,
+        competition_category: request.competition_category.map(|c| c.to_string()),
+        round: request.round,
+        place: request.place,
+        wind: score.wind.wind,
+        timing: request.timing.to_string(),
+        track: request.track.to_string(),
+        notes: request.notes,
+        edition: score.edition,
+        scored_mark: score.mark,
+        points: score.points.map(|p| p.points),
+        lower_is_better: score.lower_is_better,
+        wind_legal: score.wind.wind_legal,
+    })
+}
+
+fn database_error(e: sqlx::Error) -> (StatusCode, Json<serde_json::Value>) {
+    let error_response = serde_json::json!({
+        "status": "error",
+        "message": format!("Database error: { }", e),
+    });
+    (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+}
+
+fn not_found(id: i32) -> (StatusCode, Json<serde_json::Value>) {
+    let error_response = serde_json::json!({
+        "status": "error",
+        "message": format!("Performance {} not found", id),
+    });
+    (StatusCode::NOT_FOUND, Json(error_response))
+}
+
+fn season_out_of_range(season: i32) -> (StatusCode, Json<serde_json::Value>) {
+    let error_response = serde_json::json!({
+        "status": "Bad Request",
+        "message": format!("Season {} is out of range", season),
+    });
+    (StatusCode::BAD_REQUEST, Json(error_response))
+}
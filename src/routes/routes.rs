@@ -1,22 +1,43 @@
 #![allow(dead_code)]
-use std::sync::Arc;
+use std::{
+    collections::HashSet,
+    sync::{Arc, Mutex, RwLock},
+};
 
-use crate::models::token::TokenDetails;
+use crate::{models::{import_job::{ImportJob, ImportTable}, token::TokenDetails}, scoring::engine::ScoringEngine};
 
 use super::{
+    age_grading::{get_age_grade_handler, get_user_age_grade_handler},
     auth::{login_handler, logout_handler, refresh_access_token_handler},
+    bulk_scoring::bulk_score_handler,
+    combined_events::score_combined_events_handler,
+    edition_diff::get_edition_diff_handler,
+    equivalents::get_equivalents_handler,
     files::upload_file,
-    iaaf_points::{add_user_points_handler, delete_user_points_handler, get_user_points_handler, get_value, read_iaaf_json},
-    jwt_auth::auth,
+    iaaf_points::{
+        get_editions_handler, get_event_catalog_handler, get_import_job_handler, get_lookup_cache_stats_handler,
+        get_marks_for_points_handler, get_value, read_iaaf_json, upload_points_handler,
+    },
+    jwt_auth::{admin, auth},
+    leaderboards::{get_event_leaderboard_handler, get_points_leaderboard_handler},
+    performances::{
+        create_performance_handler, delete_performance_handler, get_bests_handler, get_performance_handler, get_performances_handler,
+        get_progression_handler, update_performance_handler,
+    },
+    utils::{constants::MAX_POINTS_UPLOAD_BYTES, lookup_cache::LookupCache},
+    scoring_curves::get_scoring_curve_handler,
+    scoring_engine::{fit_engine_handler, get_engine_points_handler, verify_engine_handler},
     system_info::{get_system_details_handler, realtime_cpu_handler},
+    world_rankings::{get_ranking_score_handler, get_user_ranking_handler},
     users::{
         create_user_handler, get_user_details_handler, get_users_handler, update_user_handler,
     },
 };
 
 use axum::{
+    extract::DefaultBodyLimit,
     middleware,
-    routing::{delete, get, post, put},
+    routing::{get, post, put},
     Router,
 };
 use moka::future::Cache;
@@ -31,15 +52,29 @@ pub struct AppState {
     pub env: crate::config::Config,
     pub tx: broadcast::Sender<Snapshot>,
     pub cache: Cache<Uuid, TokenDetails>,
+    pub engine: RwLock<ScoringEngine>,
+    pub import_jobs: Cache<Uuid, Arc<Mutex<ImportJob>>>,
+    /// The table and edition of every import in progress, so the same edition is not imported twice
+    /// at once.
+    pub running_imports: Mutex<HashSet<(ImportTable, i32)>>,
+    pub lookups: LookupCache,
 }
 
 pub fn create_router(app_state: Arc<AppState>) -> Router {
     let user_routes: Router<Arc<AppState>> = Router::new()
         .route("/", get(get_users_handler))
-        .route("/user_points/:user_id/:points_id", post(add_user_points_handler))
-        .route("/user_points/:user_id/:points_id", delete(delete_user_points_handler), )
-        .route("/user_points/:user_id", get(get_user_points_handler))
+        .route("/performances", get(get_performances_handler).post(create_performance_handler))
+        .route("/performances/bests", get(get_bests_handler))
+        .route("/performances/progression", get(get_progression_handler))
+        .route("/performances/ranking", get(get_user_ranking_handler))
+        .route("/leaderboards/:category/:gender", get(get_points_leaderboard_handler))
+        .route("/leaderboards/:category/:gender/:event", get(get_event_leaderboard_handler))
+        .route(
+            "/performances/:id",
+            get(get_performance_handler).put(update_performance_handler).delete(delete_performance_handler),
+        )
         .route("/me", get(get_user_details_handler))
+        .route("/age_grade/:category/:event", get(get_user_age_grade_handler))
         .route("/:id", put(update_user_handler))
         .route_layer(middleware::from_fn_with_state(app_state.clone(), auth));
 
@@ -49,9 +84,35 @@ pub fn create_router(app_state: Arc<AppState>) -> Router {
     let health_check_routes: Router<Arc<AppState>> =
         Router::new().route("/check", get(super::health_check::health_check));
 
-    let points_routes: Router<Arc<AppState>> = Router::new()
+    // Loading scoring tables and fitting the engine change what every user is scored against, and
+    // verifying the engine reads the whole points table.
+    let admin_points_routes: Router<Arc<AppState>> = Router::new()
         .route("/read", get(read_iaaf_json))
-        .route("/points/:category/:gender/:event", get(get_value));
+        .route("/read/:job_id", get(get_import_job_handler))
+        .route(
+            "/upload",
+            post(upload_points_handler).layer(DefaultBodyLimit::max(MAX_POINTS_UPLOAD_BYTES)),
+        )
+        .route("/engine/fit", post(fit_engine_handler))
+        .route("/engine/verify", get(verify_engine_handler))
+        .route_layer(middleware::from_fn_with_state(app_state.clone(), admin))
+        .route_layer(middleware::from_fn_with_state(app_state.clone(), auth));
+
+    let points_routes: Router<Arc<AppState>> = Router::new()
+        .route("/events", get(get_event_catalog_handler))
+        .route("/editions", get(get_editions_handler))
+        .route("/editions/diff", get(get_edition_diff_handler))
+        .route("/cache/stats", get(get_lookup_cache_stats_handler))
+        .route("/points/bulk", post(bulk_score_handler))
+        .route("/points/:category/:gender/:event", get(get_value))
+        .route("/marks/:category/:gender/:event", get(get_marks_for_points_handler))
+        .route("/equivalents/:category/:gender/:event", get(get_equivalents_handler))
+        .route("/curve/:category/:gender/:event", get(get_scoring_curve_handler))
+        .route("/age_grade/:category/:gender/:event", get(get_age_grade_handler))
+        .route("/ranking_score/:category/:gender/:event", get(get_ranking_score_handler))
+        .route("/combined/:category/:gender/:event", post(score_combined_events_handler))
+        .route("/engine/points/:category/:gender/:event", get(get_engine_points_handler))
+        .merge(admin_points_routes);
 
     let system_routes: Router<Arc<AppState>> = Router::new()
         .route("/cpu", get(realtime_cpu_handler)) //web socket
@@ -0,0 +1,101 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Query, State},
+    http::{
+        header::{CONTENT_DISPOSITION, CONTENT_TYPE},
+        StatusCode,
+    },
+    response::{IntoResponse, Response},
+    Json,
+};
+
+use crate::{
+    models::iaaf_points::{Category, CurveFormat, CurveQueryParams, CurveSlice, Gender, ScoringCurve},
+    scoring::marks::parse_mark,
+};
+
+use super::{database_functions::athletics_db::get_scoring_curve, routes::AppState};
+
+/// Returns the `(mark, points)` series of an event's points table for charting.
+///
+/// The series can be limited to a points range and a mark range and down-sampled to every `step`th
+/// row. It is served as JSON, or as a CSV file with `points`, `mark`, `mark_display` and
+/// `mark_imperial` columns when `format=csv`.
+pub async fn get_scoring_curve_handler(
+    Path((category, gender, event)): Path<(Category, Gender, String)>,
+    Query(params): Query<CurveQueryParams>,
+    State(data): State<Arc<AppState>>,
+) -> Result<Response, (StatusCode, Json<serde_json::Value>)> {
+    let bad_request = |message: String| {
+        let error_response = serde_json::json!({
+            "status": "Bad Request",
+            "message": message,
+        });
+        (StatusCode::BAD_REQUEST, Json(error_response))
+    };
+    let parse_bound = |mark: &Option<String>| {
+        mark.as_deref().map(parse_mark).transpose().map_err(|e| bad_request(format!("Invalid mark: {}", e)))
+    };
+
+    let step = params.step.unwrap_or(1);
+    if step < 1 {
+        return Err(bad_request("The step must be at least 1".to_string()));
+    }
+    let slice = CurveSlice {
+        edition: params.edition,
+        min_points: params.min_points,
+        max_points: params.max_points,
+        min_mark: parse_bound(&params.min_mark)?,
+        max_mark: parse_bound(&params.max_mark)?,
+        step,
+    };
+
+    let rows = get_scoring_curve(&data.db, &category, &gender, &event, &slice).await.map_err(|e| {
+        let error_response = serde_json::json!({
+            "status": "error",
+            "message": format!("Database error: { }", e),
+        });
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+    let Some(curve) = ScoringCurve::from_rows(&category, &gender, step, rows) else {
+        let error_response = serde_json::json!({
+            "status": "error",
+            "message": format!("No points table rows found for {} {} {} in the requested range", category, gender, event),
+        });
+        return Err((StatusCode::NOT_FOUND, Json(error_response)));
+    };
+
+    let file_name = format!("curve_{}_{}_{}_{}", curve.category, curve.gender, curve.event, curve.edition).replace(' ', "_");
+    match params.format {
+        CurveFormat::Json if params.download => {
+            let disposition = format!("attachment; filename=\"{}.json\"", file_name);
+            Ok(([(CONTENT_DISPOSITION, disposition)], Json(curve)).into_response())
+        }
+        CurveFormat::Json => Ok(Json(curve).into_response()),
+        CurveFormat::Csv => {
+            let csv = curve_csv(&curve).map_err(|e| {
+                let error_response = serde_json::json!({
+                    "status": "error",
+                    "message": format!("Could not write CSV: {}", e),
+                });
+                (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+            })?;
+            let disposition = if params.download { "attachment" } else { "inline" };
+            let headers = [
+                (CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+                (CONTENT_DISPOSITION, format!("{}; filename=\"{}.csv\"", disposition, file_name)),
+            ];
+            Ok((headers, csv).into_response())
+        }
+    }
+}
+
+fn curve_csv(curve: &ScoringCurve) -> Result<String, csv::Error> {
+    let mut writer = csv::Writer::from_writer(vec![]);
+    for point in &curve.series {
+        writer.serialize(point)?;
+    }
+    let bytes = writer.into_inner().map_err(|e| e.into_error())?;
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
@@ -0,0 +1,116 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+
+use crate::{
+    models::iaaf_points::{Category, Gender, PointsSearchQueryParams},
+    scoring::{engine::ScoringEngine, marks::parse_scorable_mark},
+};
+
+use super::{database_functions::athletics_db::get_all_points, routes::AppState};
+
+/// Scores a mark with the in-process scoring engine instead of the points table.
+///
+/// The engine holds one model per event, fitted to a single edition of its table, so asking for any
+/// other edition is rejected.
+pub async fn get_engine_points_handler(
+    Path((category, gender, event)): Path<(Category, Gender, String)>,
+    Query(params): Query<PointsSearchQueryParams>,
+    State(data): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let mark = params.mark.as_deref().map(parse_scorable_mark).transpose().map_err(|e| {
+        let error_response = serde_json::json!({
+            "status": "Bad Request",
+            "message": format!("Invalid mark: {}", e),
+        });
+        (StatusCode::BAD_REQUEST, Json(error_response))
+    })?;
+    let Some(mark) = mark else {
+        let bad_json = serde_json::json!({
+            "status": "Bad Request",
+            "message": "The mark query parameter is required",
+        });
+        return Err((StatusCode::BAD_REQUEST, Json(bad_json)));
+    };
+
+    let score = data
+        .engine
+        .read()
+        .unwrap()
+        .score(
+            &category,
+            &gender.to_string(),
+            &event,
+            mark,
+            params.timing.unwrap_or_default(),
+            params.track.unwrap_or_default(),
+        );
+
+    match score {
+        Some(score) if params.edition.is_some_and(|edition| score.edition != Some(edition)) => {
+            let error_response = serde_json::json!({
+                "status": "Bad Request",
+                "message": format!(
+                    "The scoring engine is fitted to edition {} for {} {} {}, use the points lookup for other editions",
+                    score.edition.map_or_else(|| "unknown".to_string(), |e| e.to_string()),
+                    category,
+                    gender,
+                    event
+                ),
+            });
+            Err((StatusCode::BAD_REQUEST, Json(error_response)))
+        }
+        Some(score) => Ok(Json(serde_json::json!(score))),
+        None => {
+            let error_response = serde_json::json!({
+                "status": "error",
+                "message": format!("The scoring engine has no model for {} {} {}", category, gender, event),
+            });
+            Err((StatusCode::NOT_FOUND, Json(error_response)))
+        }
+    }
+}
+
+/// Refits the scoring engine from the rows currently in the points table.
+pub async fn fit_engine_handler(
+    State(data): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let rows = get_all_points(&data.db, None).await.map_err(|e| {
+        let error_response = serde_json::json!({
+            "status": "error",
+            "message": format!("Database error: { }", e),
+        });
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let engine = ScoringEngine::fit(&rows);
+    let json_response = serde_json::json!({
+        "status": "success",
+        "models": engine.models(),
+    });
+    *data.engine.write().unwrap() = engine;
+
+    Ok(Json(json_response))
+}
+
+/// Checks the scoring engine against every stored points table row and reports any discrepancies.
+pub async fn verify_engine_handler(
+    State(data): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let rows = get_all_points(&data.db, None).await.map_err(|e| {
+        let error_response = serde_json::json!({
+            "status": "error",
+            "message": format!("Database error: { }", e),
+        });
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let report = data.engine.read().unwrap().verify(&rows);
+
+    Ok(Json(serde_json::json!(report)))
+}
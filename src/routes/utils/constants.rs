@@ -2,9 +2,13 @@ pub const TOKEN_NOT_FOUND: &str = "Please login again no token found. Invalid to
 pub const USER_NOT_FOUND: &str = "User does not exist";
 pub const NOT_LOGGED_IN: &str = "You are not logged in, please provide token";
 pub const INVALID_TOKEN: &str = "Invalid Token";
+pub const NOT_AN_ADMIN: &str = "You must be an administrator to do this";
 
 pub const RESPONSE_STATUS_FAIL: &str = "FAIL";
 pub const RESPONSE_STATUS_SUCCESS: &str = "SUCCESS";
 
 pub const ACCESS_TOKEN: &str = "access_token";
 
+
+/// The largest scoring table file accepted by the points upload route.
+pub const MAX_POINTS_UPLOAD_BYTES: usize = 512 * 1024 * 1024;
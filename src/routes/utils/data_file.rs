@@ -0,0 +1,192 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::marker::PhantomData;
+use std::path::Path;
+
+use serde::de::{DeserializeOwned, Deserializer, SeqAccess, Visitor};
+use tokio::sync::mpsc;
+
+use crate::models::age_grading::AgeFactor;
+use crate::models::iaaf_points::PointsInsert;
+use crate::scoring::marks::parse_mark;
+
+/// A row read from a data file, or why it could not be read.
+pub type ParsedRow<T> = Result<T, String>;
+
+/// The formats data files are accepted in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataFileFormat {
+    /// A JSON array of row objects, e.g. `{Points, Gender, Category, Event, Mark}` for points tables.
+    Json,
+    /// A CSV file with a header row naming the columns in any order.
+    Csv,
+}
+
+impl DataFileFormat {
+    /// Picks the format from a file name, JSON unless it ends in `.csv`.
+    pub fn from_file_name(name: &str) -> Self {
+        if name.to_lowercase().ends_with(".csv") {
+            DataFileFormat::Csv
+        } else {
+            DataFileFormat::Json
+        }
+    }
+
+    /// Picks the format from a content type, `None` if it is neither JSON nor CSV.
+    pub fn from_content_type(content_type: &str) -> Option<Self> {
+        if content_type.starts_with("text/csv") {
+            Some(DataFileFormat::Csv)
+        } else if content_type.starts_with("application/json") {
+            Some(DataFileFormat::Json)
+        } else {
+            None
+        }
+    }
+}
+
+/// A row type that can be read from a JSON or CSV data file. JSON rows are deserialized directly,
+/// CSV rows are built from their fields.
+pub trait DataFileRow: DeserializeOwned + Send + 'static {
+    /// The columns a CSV file must have, matched case-insensitively.
+    const CSV_COLUMNS: &'static [&'static str];
+
+    /// Builds a row from a CSV record. `field` gives the value of a column by its lowercase name,
+    /// empty when the file does not have the column.
+    fn from_csv<'a>(field: impl Fn(&str) -> &'a str) -> Result<Self, String>;
+}
+
+impl DataFileRow for PointsInsert {
+    const CSV_COLUMNS: &'static [&'static str] = &["points", "gender", "category", "event", "mark"];
+
+    fn from_csv<'a>(field: impl Fn(&str) -> &'a str) -> Result<Self, String> {
+        Ok(PointsInsert::new(
+            None,
+            field("points").parse().map_err(|_| format!("Invalid points '{}'", field("points")))?,
+            field("gender").to_string(),
+            field("category").to_string(),
+            field("event").to_string(),
+            parse_mark(field("mark")).map_err(|e| e.to_string())?,
+            optional_edition(field("edition"))?,
+        ))
+    }
+}
+
+impl DataFileRow for AgeFactor {
+    const CSV_COLUMNS: &'static [&'static str] = &["gender", "event", "age", "factor", "open_standard"];
+
+    fn from_csv<'a>(field: impl Fn(&str) -> &'a str) -> Result<Self, String> {
+        Ok(AgeFactor {
+            gender: field("gender").to_string(),
+            event: field("event").to_string(),
+            age: field("age").parse().map_err(|_| format!("Invalid age '{}'", field("age")))?,
+            factor: field("factor").parse().map_err(|_| format!("Invalid factor '{}'", field("factor")))?,
+            open_standard: parse_mark(field("open_standard")).map_err(|e| e.to_string())?,
+            edition: optional_edition(field("edition"))?,
+        })
+    }
+}
+
+fn optional_edition(value: &str) -> Result<Option<i32>, String> {
+    match value {
+        "" => Ok(None),
+        value => value.parse().map(Some).map_err(|_| format!("Invalid edition '{}'", value)),
+    }
+}
+
+/// Parses a data file row by row, sending each row to `rows` as soon as it is read so the whole
+/// file is never held in memory. This blocks, so run it with `spawn_blocking`. Parsing stops early
+/// when the receiving end is dropped.
+///
+/// Rows with bad values are sent as errors and parsing carries on. An error is returned when the
+/// file itself is malformed, after every row before the problem has been sent.
+pub fn parse_rows<T: DataFileRow>(
+    reader: impl Read,
+    format: DataFileFormat,
+    rows: mpsc::Sender<ParsedRow<T>>,
+) -> Result<(), String> {
+    let reader = BufReader::new(reader);
+    match format {
+        DataFileFormat::Json => parse_json(reader, rows),
+        DataFileFormat::Csv => parse_csv(reader, rows),
+    }
+}
+
+/// Reads every valid row of a points file, for callers that need the whole table such as the scoring
+/// engine.
+pub async fn read_points_file(path: &Path) -> Result<Vec<PointsInsert>, String> {
+    let file = File::open(path).map_err(|e| e.to_string())?;
+    let format = DataFileFormat::from_file_name(&path.to_string_lossy());
+
+    let (tx, mut rx) = mpsc::channel(1_000);
+    let parser = tokio::task::spawn_blocking(move || parse_rows(file, format, tx));
+
+    let mut models = vec![];
+    while let Some(row) = rx.recv().await {
+        if let Ok(model) = row {
+            models.push(model);
+        }
+    }
+    parser.await.map_err(|e| e.to_string())??;
+
+    Ok(models)
+}
+
+fn parse_json<T: DataFileRow>(reader: impl Read, rows: mpsc::Sender<ParsedRow<T>>) -> Result<(), String> {
+    struct RowVisitor<T>(mpsc::Sender<ParsedRow<T>>, PhantomData<T>);
+
+    impl<'de, T: DataFileRow> Visitor<'de> for RowVisitor<T> {
+        type Value = ();
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "an array of table rows")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+            // Each element goes through a `Value` so one bad row does not end the whole file.
+            while let Some(value) = seq.next_element::<serde_json::Value>()? {
+                let row = serde_json::from_value(value).map_err(|e| e.to_string());
+                if self.0.blocking_send(row).is_err() {
+                    return Ok(());
+                }
+            }
+            Ok(())
+        }
+    }
+
+    let closed = rows.clone();
+    let mut deserializer = serde_json::Deserializer::from_reader(reader);
+    match deserializer.deserialize_seq(RowVisitor(rows, PhantomData)) {
+        Ok(()) => deserializer.end().map_err(|e| format!("Invalid JSON: {}", e)),
+        // Stopping early leaves the array unfinished, which is only an error if nobody stopped us.
+        Err(_) if closed.is_closed() => Ok(()),
+        Err(e) => Err(format!("Invalid JSON: {}", e)),
+    }
+}
+
+fn parse_csv<T: DataFileRow>(reader: impl Read, rows: mpsc::Sender<ParsedRow<T>>) -> Result<(), String> {
+    let mut reader = csv::ReaderBuilder::new().trim(csv::Trim::All).from_reader(reader);
+
+    let headers: HashMap<String, usize> = reader
+        .headers()
+        .map_err(|e| format!("Invalid CSV header: {}", e))?
+        .iter()
+        .enumerate()
+        .map(|(index, name)| (name.to_lowercase(), index))
+        .collect();
+    if let Some(missing) = T::CSV_COLUMNS.iter().find(|name| !headers.contains_key(**name)) {
+        return Err(format!("CSV is missing the '{}' column", missing));
+    }
+
+    for record in reader.records() {
+        let row = record
+            .map_err(|e| e.to_string())
+            .and_then(|record| T::from_csv(|name| headers.get(name).and_then(|i| record.get(*i)).unwrap_or_default()));
+        if rows.blocking_send(row).is_err() {
+            break;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,157 @@
+use std::future::Future;
+use std::hash::Hash;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use moka::future::Cache;
+use serde::Serialize;
+
+use crate::models::iaaf_points::{MarkQuery, MarkRange, MarkScore};
+use crate::scoring::{events::resolve_event, timing::TimingMethod, track::TrackType};
+
+/// The most lookups of each kind kept in memory.
+const LOOKUP_CACHE_CAPACITY: u64 = 100_000;
+/// How long a cached lookup is kept. Imports clear the cache, this only guards against anything
+/// written to the points table outside an import.
+const LOOKUP_CACHE_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// A points lookup for a mark, everything that changes its result.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MarkLookupKey {
+    category: String,
+    gender: String,
+    event: String,
+    mark: u64,
+    edition: Option<i32>,
+    timing: TimingMethod,
+    wind: Option<u64>,
+    track: TrackType,
+}
+
+impl From<&MarkQuery> for MarkLookupKey {
+    fn from(query: &MarkQuery) -> Self {
+        Self {
+            category: query.category.to_string(),
+            gender: query.gender.to_string(),
+            event: resolve_event(&query.event).to_lowercase(),
+            mark: query.mark.to_bits(),
+            edition: query.edition,
+            timing: query.timing,
+            wind: query.wind.map(f64::to_bits),
+            track: query.track,
+        }
+    }
+}
+
+/// A marks lookup for a points value.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PointsLookupKey {
+    category: String,
+    gender: String,
+    event: String,
+    points: i32,
+    edition: Option<i32>,
+}
+
+impl PointsLookupKey {
+    pub fn new(category: String, gender: String, event: &str, points: i32, edition: Option<i32>) -> Self {
+        Self { category, gender, event: resolve_event(event).to_lowercase(), points, edition }
+    }
+}
+
+/// Hit and miss counts for one kind of lookup.
+#[derive(Debug, Serialize, Clone)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    /// The share of lookups answered from the cache, `None` before the first lookup.
+    pub hit_rate: Option<f64>,
+    pub entries: u64,
+}
+
+/// A moka cache that counts its hits and misses.
+pub struct CountingCache<K, V> {
+    cache: Cache<K, V>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl<K, V> CountingCache<K, V>
+where
+    K: Hash + Eq + Send + Sync + 'static,
+    V: Clone + Send + Sync + 'static,
+{
+    fn new() -> Self {
+        Self {
+            cache: Cache::builder().max_capacity(LOOKUP_CACHE_CAPACITY).time_to_live(LOOKUP_CACHE_TTL).build(),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        let (hits, misses) = (self.hits.load(Ordering::Relaxed), self.misses.load(Ordering::Relaxed));
+        CacheStats {
+            hits,
+            misses,
+            hit_rate: (hits + misses > 0).then(|| hits as f64 / (hits + misses) as f64),
+            entries: self.cache.entry_count(),
+        }
+    }
+}
+
+/// Caches points table lookups, which only change when a table is imported.
+pub struct LookupCache {
+    pub scores: CountingCache<MarkLookupKey, Option<MarkScore>>,
+    pub ranges: CountingCache<PointsLookupKey, Option<MarkRange>>,
+    /// Bumped whenever the cache is cleared, so lookups that started before an import do not store
+    /// results read from the old table.
+    generation: AtomicU64,
+}
+
+impl Default for LookupCache {
+    fn default() -> Self {
+        Self { scores: CountingCache::new(), ranges: CountingCache::new(), generation: AtomicU64::new(0) }
+    }
+}
+
+impl LookupCache {
+    /// Returns the cached value for a key, or runs `lookup` and caches what it returns. Errors are
+    /// passed through and not cached.
+    pub async fn get_or_fetch<K, V, E>(
+        &self,
+        cache: &CountingCache<K, V>,
+        key: K,
+        lookup: impl Future<Output = Result<V, E>>,
+    ) -> Result<V, E>
+    where
+        K: Hash + Eq + Clone + Send + Sync + 'static,
+        V: Clone + Send + Sync + 'static,
+    {
+        if let Some(value) = cache.cache.get(&key).await {
+            cache.hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(value);
+        }
+        cache.misses.fetch_add(1, Ordering::Relaxed);
+
+        let generation = self.generation.load(Ordering::Acquire);
+        let value = lookup.await?;
+        cache.cache.insert(key.clone(), value.clone()).await;
+        if self.generation.load(Ordering::Acquire) != generation {
+            cache.cache.invalidate(&key).await;
+        }
+        Ok(value)
+    }
+
+    /// Clears every cached lookup, called after the points table changes.
+    pub fn invalidate(&self) {
+        self.generation.fetch_add(1, Ordering::AcqRel);
+        self.scores.cache.invalidate_all();
+        self.ranges.cache.invalidate_all();
+    }
+
+    /// The number of times the cache has been cleared.
+    pub fn invalidations(&self) -> u64 {
+        self.generation.load(Ordering::Acquire)
+    }
+}
@@ -1,3 +1,5 @@
 
 pub mod token;
-pub mod constants;
\ No newline at end of file
+pub mod constants;
+pub mod data_file;
+pub mod lookup_cache;
\ No newline at end of file
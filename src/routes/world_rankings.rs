@@ -0,0 +1,116 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Extension, Json,
+};
+
+use crate::{
+    models::{
+        iaaf_points::{Category, Gender, MarkQuery, RankingScoreQueryParams},
+        performance::{Performance, PerformanceQueryParams, RankingQueryParams},
+    },
+    scoring::{
+        marks::parse_scorable_mark,
+        world_rankings::{placing_score, ranking_averages, Round},
+    },
+};
+
+use super::{
+    database_functions::{athletics_db::get_points_for_mark, performances_db::get_performances},
+    jwt_auth::JWTAuthMiddleware,
+    routes::AppState,
+};
+
+/// Works out the World Athletics ranking performance score for a mark: the result score from the
+/// points table plus the placing score for the place in the competition.
+pub async fn get_ranking_score_handler(
+    Path((category, gender, event)): Path<(Category, Gender, String)>,
+    Query(params): Query<RankingScoreQueryParams>,
+    State(data): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let mark = params.mark.as_deref().map(parse_scorable_mark).transpose().map_err(|e| {
+        let error_response = serde_json::json!({
+            "status": "Bad Request",
+            "message": format!("Invalid mark: {}", e),
+        });
+        (StatusCode::BAD_REQUEST, Json(error_response))
+    })?;
+    let Some(mark) = mark else {
+        let bad_json = serde_json::json!({
+            "status": "Bad Request",
+            "message": "The mark query parameter is required",
+        });
+        return Err((StatusCode::BAD_REQUEST, Json(bad_json)));
+    };
+
+    let query = MarkQuery {
+        category: category.clone(),
+        gender: gender.clone(),
+        event: event.clone(),
+        mark,
+        edition: params.edition,
+        timing: params.timing.unwrap_or_default(),
+        wind: params.wind,
+        track: params.track.unwrap_or_default(),
+    };
+    let score = get_points_for_mark(&data.db, query).await.map_err(|e| {
+        let error_response = serde_json::json!({
+            "status": "error",
+            "message": format!("Database error: { }", e),
+        });
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+    let Some(score) = score else {
+        let error_response = serde_json::json!({
+            "status": "error",
+            "message": format!("No points table found for {} {} {}", category, gender, event),
+        });
+        return Err((StatusCode::NOT_FOUND, Json(error_response)));
+    };
+
+    let result_score = score.points.as_ref().map(|p| p.points);
+    let placing_score = placing_score(params.competition_category, params.place, params.round.as_deref());
+    let json_response = serde_json::json!({
+        "competition_category": params.competition_category,
+        "place": params.place,
+        "round": params.round,
+        "placing_round": Round::from_name(params.round.as_deref()),
+        "result_score": result_score,
+        "placing_score": placing_score,
+        "ranking_score": result_score.map(|points| points + placing_score),
+        "result": score,
+    });
+    Ok(Json(json_response))
+}
+
+/// Works out the logged in user's World Athletics ranking average in each event from their best
+/// ranking scores in the ranking window.
+pub async fn get_user_ranking_handler(
+    Extension(jwtauth): Extension<JWTAuthMiddleware>,
+    Query(params): Query<RankingQueryParams>,
+    State(data): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let date = params.date.unwrap_or_else(|| chrono::Local::now().date_naive());
+    let filter = PerformanceQueryParams { category: params.category, event: params.event, from: None, to: Some(date) };
+    let performances: Vec<Performance> = get_performances(&data.db, jwtauth.user.id, &filter)
+        .await
+        .map_err(|e| {
+            let error_response = serde_json::json!({
+                "status": "error",
+                "message": format!("Database error: { }", e),
+            });
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?
+        .into_iter()
+        .map(Performance::from)
+        .collect();
+
+    let json_response = serde_json::json!({
+        "date": date,
+        "rankings": ranking_averages(&performances, date, params.results, params.months),
+    });
+    Ok(Json(json_response))
+}
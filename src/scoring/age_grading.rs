@@ -0,0 +1,59 @@
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use super::marks::{format_mark, round_mark};
+
+/// A mark graded against the WMA standard for the athlete's age.
+///
+/// WMA age factors scale the open class standard, roughly the world record, to what is expected at
+/// a given age. A factor of 1.0 means no allowance for age.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct AgeGrade {
+    pub age: i32,
+    pub factor: f64,
+    pub open_standard: f64,
+    pub open_standard_display: String,
+    /// The open standard adjusted for age: slower or shorter than the open standard.
+    pub age_standard: f64,
+    pub age_standard_display: String,
+    /// The mark converted to its open class equivalent, what it would be worth without the age allowance.
+    pub age_graded_mark: f64,
+    pub age_graded_mark_display: String,
+    /// The mark as a percentage of the age standard. 100% is world record level for the age.
+    pub age_grade_percent: f64,
+}
+
+/// Grades a mark with the age factor and open standard for an athlete's age and event.
+///
+/// Times are multiplied by the factor and compared with the open standard divided by it, distances
+/// are divided by the factor and compared with the open standard multiplied by it. Marks are rounded
+/// the way results are, see [`round_mark`].
+pub fn age_grade(mark: f64, lower_is_better: bool, age: i32, factor: f64, open_standard: f64) -> AgeGrade {
+    let (age_standard, age_graded_mark, percent) = if lower_is_better {
+        let age_standard = open_standard / factor;
+        (age_standard, mark * factor, age_standard / mark)
+    } else {
+        let age_standard = open_standard * factor;
+        (age_standard, mark / factor, mark / age_standard)
+    };
+    let (age_standard, age_graded_mark) =
+        (round_mark(age_standard, lower_is_better), round_mark(age_graded_mark, lower_is_better));
+
+    AgeGrade {
+        age,
+        factor,
+        open_standard,
+        open_standard_display: format_mark(open_standard, lower_is_better),
+        age_standard,
+        age_standard_display: format_mark(age_standard, lower_is_better),
+        age_graded_mark,
+        age_graded_mark_display: format_mark(age_graded_mark, lower_is_better),
+        age_grade_percent: (percent * 10_000.0).round() / 100.0,
+    }
+}
+
+/// An athlete's age in whole years on a date, the age WMA grades a mark at.
+pub fn age_on(date_of_birth: NaiveDate, date: NaiveDate) -> i32 {
+    let had_birthday = (date.month(), date.day()) >= (date_of_birth.month(), date_of_birth.day());
+    date.year() - date_of_birth.year() - if had_birthday { 0 } else { 1 }
+}
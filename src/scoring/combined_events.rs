@@ -0,0 +1,331 @@
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::models::iaaf_points::{Category, Gender};
+
+use super::{
+    events::resolve_event,
+    marks::{check_mark, format_imperial_mark, format_mark, round_mark, MarkParseError},
+};
+use DisciplineKind::{Jump, Throw, Track};
+
+/// How a discipline's mark is fed into the combined events formula.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DisciplineKind {
+    /// Points = A * (B - T)^C with T in seconds.
+    Track,
+    /// Points = A * (M - B)^C with M in centimetres.
+    Jump,
+    /// Points = A * (D - B)^C with D in metres.
+    Throw,
+}
+
+/// One discipline of a combined event with its official scoring coefficients.
+#[derive(Debug, Clone, Copy)]
+pub struct Discipline {
+    pub event: &'static str,
+    pub kind: DisciplineKind,
+    pub a: f64,
+    pub b: f64,
+    pub c: f64,
+}
+
+impl Discipline {
+    const fn new(event: &'static str, kind: DisciplineKind, a: f64, b: f64, c: f64) -> Self {
+        Self { event, kind, a, b, c }
+    }
+
+    pub fn lower_is_better(&self) -> bool {
+        self.kind == DisciplineKind::Track
+    }
+
+    /// Scores a mark (seconds or metres) after rounding it the same way as the single event tables.
+    /// Marks beyond the formula's zero point score nothing.
+    pub fn points(&self, mark: f64) -> i32 {
+        self.formula_points(round_mark(mark, self.lower_is_better()))
+    }
+
+    /// Scores a mark with the formula alone, without rounding it first.
+    fn formula_points(&self, mark: f64) -> i32 {
+        let base = match self.kind {
+            DisciplineKind::Track => self.b - mark,
+            DisciplineKind::Jump => (mark * 100.0).round() - self.b,
+            DisciplineKind::Throw => mark - self.b,
+        };
+
+        if base <= 0.0 {
+            return 0;
+        }
+        (self.a * base.powf(self.c)).floor() as i32
+    }
+}
+
+const MEN_DECATHLON: [Discipline; 10] = [
+    Discipline::new("100m", Track, 25.4347, 18.0, 1.81),
+    Discipline::new("LJ", Jump, 0.14354, 220.0, 1.4),
+    Discipline::new("SP", Throw, 51.39, 1.5, 1.05),
+    Discipline::new("HJ", Jump, 0.8465, 75.0, 1.42),
+    Discipline::new("400m", Track, 1.53775, 82.0, 1.81),
+    Discipline::new("110mH", Track, 5.74352, 28.5, 1.92),
+    Discipline::new("DT", Throw, 12.91, 4.0, 1.1),
+    Discipline::new("PV", Jump, 0.2797, 100.0, 1.35),
+    Discipline::new("JT", Throw, 10.14, 7.0, 1.08),
+    Discipline::new("1500m", Track, 0.03768, 480.0, 1.85),
+];
+
+const WOMEN_DECATHLON: [Discipline; 10] = [
+    Discipline::new("100m", Track, 17.857, 21.0, 1.81),
+    Discipline::new("DT", Throw, 12.3311, 3.0, 1.1),
+    Discipline::new("PV", Jump, 0.44125, 100.0, 1.35),
+    Discipline::new("JT", Throw, 15.9803, 3.8, 1.04),
+    Discipline::new("400m", Track, 1.34285, 91.7, 1.81),
+    Discipline::new("100mH", Track, 9.23076, 26.7, 1.835),
+    Discipline::new("LJ", Jump, 0.188807, 210.0, 1.41),
+    Discipline::new("SP", Throw, 56.0211, 1.5, 1.05),
+    Discipline::new("HJ", Jump, 1.84523, 75.0, 1.348),
+    Discipline::new("1500m", Track, 0.02883, 535.0, 1.88),
+];
+
+const WOMEN_HEPTATHLON: [Discipline; 7] = [
+    Discipline::new("100mH", Track, 9.23076, 26.7, 1.835),
+    Discipline::new("HJ", Jump, 1.84523, 75.0, 1.348),
+    Discipline::new("SP", Throw, 56.0211, 1.5, 1.05),
+    Discipline::new("200m", Track, 4.99087, 42.5, 1.81),
+    Discipline::new("LJ", Jump, 0.188807, 210.0, 1.41),
+    Discipline::new("JT", Throw, 15.9803, 3.8, 1.04),
+    Discipline::new("800m", Track, 0.11193, 254.0, 1.88),
+];
+
+const MEN_INDOOR_HEPTATHLON: [Discipline; 7] = [
+    Discipline::new("60m", Track, 58.015, 11.5, 1.81),
+    Discipline::new("LJ", Jump, 0.14354, 220.0, 1.4),
+    Discipline::new("SP", Throw, 51.39, 1.5, 1.05),
+    Discipline::new("HJ", Jump, 0.8465, 75.0, 1.42),
+    Discipline::new("60mH", Track, 20.5173, 15.5, 1.92),
+    Discipline::new("PV", Jump, 0.2797, 100.0, 1.35),
+    Discipline::new("1000m", Track, 0.08713, 305.5, 1.85),
+];
+
+const WOMEN_INDOOR_PENTATHLON: [Discipline; 5] = [
+    Discipline::new("60mH", Track, 20.0479, 17.0, 1.835),
+    Discipline::new("HJ", Jump, 1.84523, 75.0, 1.348),
+    Discipline::new("SP", Throw, 56.0211, 1.5, 1.05),
+    Discipline::new("LJ", Jump, 0.188807, 210.0, 1.41),
+    Discipline::new("800m", Track, 0.11193, 254.0, 1.88),
+];
+
+/// Returns the disciplines, in competition order, of a combined event.
+pub fn disciplines(category: &Category, gender: &Gender, event: &str) -> Option<&'static [Discipline]> {
+    match (category, gender, resolve_event(event).to_lowercase().as_str()) {
+        (Category::Outdoor, Gender::Male, "decathlon") => Some(&MEN_DECATHLON),
+        (Category::Outdoor, Gender::Female, "decathlon") => Some(&WOMEN_DECATHLON),
+        (Category::Outdoor, Gender::Female, "heptathlon") => Some(&WOMEN_HEPTATHLON),
+        (Category::Indoor, Gender::Male, "heptathlon") => Some(&MEN_INDOOR_HEPTATHLON),
+        (Category::Indoor, Gender::Female, "pentathlon") => Some(&WOMEN_INDOOR_PENTATHLON),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CombinedEventsError {
+    UnknownDiscipline(String),
+    DuplicateDiscipline(String),
+    InvalidMark(String, MarkParseError),
+}
+
+impl fmt::Display for CombinedEventsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CombinedEventsError::UnknownDiscipline(event) => {
+                write!(f, "'{}' is not a discipline of this event", event)
+            }
+            CombinedEventsError::DuplicateDiscipline(event) => {
+                write!(f, "'{}' has more than one mark", event)
+            }
+            CombinedEventsError::InvalidMark(event, e) => write!(f, "'{}': {}", event, e),
+        }
+    }
+}
+
+impl std::error::Error for CombinedEventsError {}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DisciplineScore {
+    pub event: &'static str,
+    pub mark: Option<f64>,
+    pub mark_display: Option<String>,
+    /// The mark in feet and inches for jumps and throws.
+    pub mark_imperial: Option<String>,
+    pub points: i32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CombinedScore {
+    pub total: i32,
+    pub disciplines: Vec<DisciplineScore>,
+    /// Disciplines with no mark, scored as zero.
+    pub missing: Vec<&'static str>,
+}
+
+/// Scores a set of `(discipline, mark)` pairs against a combined event. Disciplines are matched by
+/// canonical event name, so aliases such as "Long Jump" work, and any discipline without a mark
+/// scores zero. Zero and negative marks are rejected.
+pub fn score(
+    disciplines: &'static [Discipline],
+    marks: &[(String, f64)],
+) -> Result<CombinedScore, CombinedEventsError> {
+    let mut found: Vec<Option<f64>> = vec![None; disciplines.len()];
+    for (event, mark) in marks {
+        let name = resolve_event(event);
+        let index = disciplines
+            .iter()
+            .position(|d| d.event.eq_ignore_ascii_case(&name))
+            .ok_or_else(|| CombinedEventsError::UnknownDiscipline(event.clone()))?;
+        check_mark(*mark).map_err(|e| CombinedEventsError::InvalidMark(event.clone(), e))?;
+        if found[index].replace(*mark).is_some() {
+            return Err(CombinedEventsError::DuplicateDiscipline(event.clone()));
+        }
+    }
+
+    let scores: Vec<DisciplineScore> = disciplines
+        .iter()
+        .zip(found)
+        .map(|(discipline, mark)| {
+            let mark = mark.map(|m| round_mark(m, discipline.lower_is_better()));
+            DisciplineScore {
+                event: discipline.event,
+                mark,
+                mark_display: mark.map(|m| format_mark(m, discipline.lower_is_better())),
+                mark_imperial: mark.and_then(|m| format_imperial_mark(m, discipline.event, discipline.lower_is_better())),
+                points: mark.map(|m| discipline.points(m)).unwrap_or(0),
+            }
+        })
+        .collect();
+
+    Ok(CombinedScore {
+        total: scores.iter().map(|s| s.points).sum(),
+        missing: scores.iter().filter(|s| s.mark.is_none()).map(|s| s.event).collect(),
+        disciplines: scores,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn marks(marks: &[(&str, f64)]) -> Vec<(String, f64)> {
+        marks.iter().map(|(event, mark)| (event.to_string(), *mark)).collect()
+    }
+
+    #[test]
+    fn hundred_metres_reference_score() {
+        let hundred = MEN_DECATHLON[0];
+        assert_eq!(hundred.formula_points(10.395), 1000);
+        // Timed marks are rounded up to the next hundredth before scoring.
+        assert_eq!(hundred.points(10.395), 999);
+        assert_eq!(hundred.points(10.39), 1001);
+    }
+
+    #[test]
+    fn jumps_are_scored_in_centimetres() {
+        let long_jump = MEN_DECATHLON[1];
+        assert_eq!(long_jump.points(7.80), 1010);
+        // Distances are rounded down to the centimetre.
+        assert_eq!(long_jump.points(7.809), 1010);
+    }
+
+    #[test]
+    fn marks_beyond_the_zero_point_score_nothing() {
+        assert_eq!(MEN_DECATHLON[0].points(18.5), 0);
+        assert_eq!(MEN_DECATHLON[2].points(1.2), 0);
+    }
+
+    #[test]
+    fn decathlon_world_record() {
+        let decathlon = disciplines(&Category::Outdoor, &Gender::Male, "Decathlon").unwrap();
+        let result = score(
+            decathlon,
+            &marks(&[
+                ("100m", 10.55),
+                ("LJ", 7.80),
+                ("SP", 16.00),
+                ("HJ", 2.05),
+                ("400m", 48.42),
+                ("110mH", 13.75),
+                ("DT", 50.54),
+                ("PV", 5.45),
+                ("JT", 71.90),
+                ("1500m", 276.11),
+            ]),
+        )
+        .unwrap();
+
+        let points: Vec<i32> = result.disciplines.iter().map(|d| d.points).collect();
+        assert_eq!(points, [963, 1010, 851, 850, 889, 1007, 882, 1051, 918, 705]);
+        assert_eq!(result.total, 9126);
+        assert!(result.missing.is_empty());
+    }
+
+    #[test]
+    fn heptathlon_world_record() {
+        let heptathlon = disciplines(&Category::Outdoor, &Gender::Female, "heptathlon").unwrap();
+        let result = score(
+            heptathlon,
+            &marks(&[
+                ("100mH", 12.69),
+                ("HJ", 1.86),
+                ("SP", 15.80),
+                ("200m", 22.56),
+                ("LJ", 7.27),
+                ("JT", 45.66),
+                ("800m", 128.51),
+            ]),
+        )
+        .unwrap();
+
+        assert_eq!(result.total, 7291);
+    }
+
+    #[test]
+    fn missing_disciplines_score_zero() {
+        let decathlon = disciplines(&Category::Outdoor, &Gender::Male, "Decathlon").unwrap();
+        let result = score(decathlon, &marks(&[("100m", 10.55), ("Long Jump", 7.80)])).unwrap();
+
+        assert_eq!(result.total, 963 + 1010);
+        assert_eq!(result.missing.len(), 8);
+        assert!(!result.missing.contains(&"LJ"));
+    }
+
+    #[test]
+    fn unknown_and_repeated_disciplines_are_rejected() {
+        let pentathlon = disciplines(&Category::Indoor, &Gender::Female, "Pentathlon").unwrap();
+
+        assert_eq!(
+            score(pentathlon, &marks(&[("JT", 50.0)])).unwrap_err(),
+            CombinedEventsError::UnknownDiscipline("JT".to_string())
+        );
+        assert_eq!(
+            score(pentathlon, &marks(&[("HJ", 1.80), ("High Jump", 1.82)])).unwrap_err(),
+            CombinedEventsError::DuplicateDiscipline("High Jump".to_string())
+        );
+    }
+
+    #[test]
+    fn zero_and_negative_marks_are_rejected() {
+        let decathlon = disciplines(&Category::Outdoor, &Gender::Male, "Decathlon").unwrap();
+
+        for (event, mark) in [("100m", 0.0), ("100m", -10.5), ("SP", 0.0), ("LJ", -7.8)] {
+            assert_eq!(
+                score(decathlon, &marks(&[(event, mark)])).unwrap_err(),
+                CombinedEventsError::InvalidMark(event.to_string(), MarkParseError::NotPositive(mark.to_string()))
+            );
+        }
+    }
+
+    #[test]
+    fn only_known_combined_events_have_disciplines() {
+        assert!(disciplines(&Category::Indoor, &Gender::Male, "Decathlon").is_none());
+        assert!(disciplines(&Category::Outdoor, &Gender::Male, "100m").is_none());
+    }
+}
@@ -0,0 +1,140 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::Serialize;
+
+use crate::models::iaaf_points::EventSummary;
+
+use super::marks::format_mark;
+
+/// The points values whose marks are compared between editions.
+pub const REPRESENTATIVE_POINTS: [i32; 7] = [100, 300, 500, 700, 900, 1100, 1300];
+
+/// The number of events listed as the largest movers.
+const MAX_MOVERS: usize = 10;
+
+/// A representative mark scored under both editions.
+#[derive(Debug, Clone)]
+pub struct SampleScore {
+    pub category: String,
+    pub gender: String,
+    pub event: String,
+    pub mark: f64,
+    pub lower_is_better: bool,
+    /// `None` if the mark is below the lowest entry of the edition's table.
+    pub from_points: Option<i32>,
+    pub to_points: Option<i32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MarkDelta {
+    pub mark: f64,
+    pub mark_display: String,
+    pub from_points: Option<i32>,
+    pub to_points: Option<i32>,
+    /// `to_points - from_points`, counting a mark below the table as zero points.
+    pub delta: i32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EventDiff {
+    pub category: String,
+    pub gender: String,
+    pub event: String,
+    pub mean_delta: f64,
+    /// The delta with the largest magnitude, keeping its sign.
+    pub max_delta: i32,
+    pub marks: Vec<MarkDelta>,
+}
+
+/// An event present in only one of the compared editions.
+#[derive(Debug, Clone, Serialize)]
+pub struct EventName {
+    pub category: String,
+    pub gender: String,
+    pub event: String,
+}
+
+/// What changed between two editions of the scoring tables.
+#[derive(Debug, Clone, Serialize)]
+pub struct EditionDiff {
+    pub from: i32,
+    pub to: i32,
+    pub events_added: Vec<EventName>,
+    pub events_removed: Vec<EventName>,
+    /// The events whose scores moved the most, by the magnitude of their mean delta.
+    pub largest_movers: Vec<EventDiff>,
+    pub events: Vec<EventDiff>,
+}
+
+type Key = (String, String, String);
+
+fn key(category: &str, gender: &str, event: &str) -> Key {
+    (category.to_lowercase(), gender.to_lowercase(), event.to_lowercase())
+}
+
+/// Builds the diff from both editions' event catalogs and the representative marks scored under
+/// each edition. Marks for events missing from either edition are ignored.
+pub fn diff(from: i32, to: i32, from_events: &[EventSummary], to_events: &[EventSummary], samples: Vec<SampleScore>) -> EditionDiff {
+    let names = |events: &[EventSummary]| -> BTreeMap<Key, EventName> {
+        events
+            .iter()
+            .map(|e| {
+                let name = EventName { category: e.category.clone(), gender: e.gender.clone(), event: e.event.clone() };
+                (key(&e.category, &e.gender, &e.event), name)
+            })
+            .collect()
+    };
+    let from_names = names(from_events);
+    let to_names = names(to_events);
+    let common: BTreeSet<&Key> = from_names.keys().filter(|k| to_names.contains_key(*k)).collect();
+
+    let mut grouped: BTreeMap<Key, Vec<SampleScore>> = BTreeMap::new();
+    for sample in samples {
+        let sample_key = key(&sample.category, &sample.gender, &sample.event);
+        if common.contains(&sample_key) {
+            grouped.entry(sample_key).or_default().push(sample);
+        }
+    }
+
+    let events: Vec<EventDiff> = grouped
+        .into_values()
+        .map(|mut samples| {
+            samples.sort_by(|a, b| a.from_points.cmp(&b.from_points).then(a.mark.total_cmp(&b.mark)));
+            samples.dedup_by(|a, b| a.mark == b.mark);
+
+            let marks: Vec<MarkDelta> = samples
+                .iter()
+                .map(|s| MarkDelta {
+                    mark: s.mark,
+                    mark_display: format_mark(s.mark, s.lower_is_better),
+                    from_points: s.from_points,
+                    to_points: s.to_points,
+                    delta: s.to_points.unwrap_or(0) - s.from_points.unwrap_or(0),
+                })
+                .collect();
+            let first = &samples[0];
+
+            EventDiff {
+                category: first.category.clone(),
+                gender: first.gender.clone(),
+                event: first.event.clone(),
+                mean_delta: marks.iter().map(|m| m.delta as f64).sum::<f64>() / marks.len() as f64,
+                max_delta: marks.iter().map(|m| m.delta).max_by_key(|d| d.abs()).unwrap_or(0),
+                marks,
+            }
+        })
+        .collect();
+
+    let mut largest_movers: Vec<EventDiff> = events.iter().filter(|e| e.max_delta != 0).cloned().collect();
+    largest_movers.sort_by(|a, b| b.mean_delta.abs().total_cmp(&a.mean_delta.abs()));
+    largest_movers.truncate(MAX_MOVERS);
+
+    EditionDiff {
+        from,
+        to,
+        events_added: to_names.iter().filter(|(k, _)| !from_names.contains_key(*k)).map(|(_, n)| n.clone()).collect(),
+        events_removed: from_names.iter().filter(|(k, _)| !to_names.contains_key(*k)).map(|(_, n)| n.clone()).collect(),
+        largest_movers,
+        events,
+    }
+}
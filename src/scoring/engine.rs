@@ -0,0 +1,467 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use crate::models::iaaf_points::{Category, PointsInsert};
+
+use super::{
+    events::resolve_event,
+    marks::{format_imperial_mark, format_mark, round_mark},
+    timing::TimingMethod,
+    track::TrackType,
+};
+
+/// The number of discrepancies kept as samples in a verification report.
+const MAX_DISCREPANCY_SAMPLES: usize = 100;
+
+/// Identifies an event's table, compared case insensitively by canonical event name.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct EventKey {
+    category: String,
+    gender: String,
+    event: String,
+}
+
+impl EventKey {
+    fn new(category: &str, gender: &str, event: &str) -> Self {
+        Self {
+            category: category.to_lowercase(),
+            gender: gender.to_lowercase(),
+            event: resolve_event(event).to_lowercase(),
+        }
+    }
+}
+
+/// The fitted scoring curve for one event.
+///
+/// World Athletics tables follow a quadratic in the mark, so points are modelled as
+/// `a * x^2 + b * x + c` where `x` is the mark normalised by `offset` and `scale` to keep the fit
+/// well conditioned.
+#[derive(Debug, Clone, Serialize)]
+pub struct EventModel {
+    pub category: String,
+    pub gender: String,
+    pub event: String,
+    /// The scoring table edition the model was fitted to, `None` for rows without one.
+    pub edition: Option<i32>,
+    pub lower_is_better: bool,
+    pub a: f64,
+    pub b: f64,
+    pub c: f64,
+    pub offset: f64,
+    pub scale: f64,
+    pub min_points: i32,
+    pub max_points: i32,
+    /// The table mark for `min_points`.
+    pub worst_mark: f64,
+    /// The table mark for `max_points`.
+    pub best_mark: f64,
+}
+
+impl EventModel {
+    /// Fits the curve to an event's table rows. Each distinct mark is fitted to the middle of the
+    /// highest points value it earns, since the table floors the formula.
+    fn fit(rows: &[&PointsInsert]) -> Option<Self> {
+        let lowest = rows.iter().min_by_key(|r| r.points)?;
+        let highest = rows.iter().max_by_key(|r| r.points)?;
+        let lower_is_better = highest.mark < lowest.mark;
+
+        let table_scores = table_scores(rows);
+        if table_scores.len() < 3 {
+            return None;
+        }
+
+        let n = table_scores.len() as f64;
+        let offset = table_scores.iter().map(|(mark, _)| mark).sum::<f64>() / n;
+        let scale = (table_scores.iter().map(|(mark, _)| (mark - offset).powi(2)).sum::<f64>() / n).sqrt();
+        if scale == 0.0 {
+            return None;
+        }
+
+        // Normal equations for a least squares quadratic.
+        let mut sums = [0.0; 5];
+        let mut targets = [0.0; 3];
+        for (mark, points) in &table_scores {
+            let x = (mark - offset) / scale;
+            let y = *points as f64 + 0.5;
+            for (power, sum) in sums.iter_mut().enumerate() {
+                *sum += x.powi(power as i32);
+            }
+            for (power, target) in targets.iter_mut().enumerate() {
+                *target += y * x.powi(power as i32);
+            }
+        }
+        let matrix = [
+            [sums[4], sums[3], sums[2]],
+            [sums[3], sums[2], sums[1]],
+            [sums[2], sums[1], sums[0]],
+        ];
+        let [a, b, c] = solve(matrix, [targets[2], targets[1], targets[0]])?;
+
+        Some(Self {
+            category: highest.category.clone(),
+            gender: highest.gender.clone(),
+            event: resolve_event(&highest.event),
+            edition: highest.edition,
+            lower_is_better,
+            a,
+            b,
+            c,
+            offset,
+            scale,
+            min_points: lowest.points,
+            max_points: highest.points,
+            worst_mark: lowest.mark,
+            best_mark: highest.mark,
+        })
+    }
+
+    /// Scores a mark that has already been rounded. Marks better than the top of the table earn the
+    /// maximum points and marks worse than the bottom earn nothing.
+    fn points(&self, mark: f64) -> Option<i32> {
+        let (beyond_best, below_worst) = if self.lower_is_better {
+            (mark <= self.best_mark, mark > self.worst_mark)
+        } else {
+            (mark >= self.best_mark, mark < self.worst_mark)
+        };
+        if beyond_best {
+            return Some(self.max_points);
+        }
+        if below_worst {
+            return None;
+        }
+
+        let x = (mark - self.offset) / self.scale;
+        let points = (self.a * x * x + self.b * x + self.c).floor() as i32;
+        Some(points.clamp(self.min_points, self.max_points))
+    }
+}
+
+/// The points the table gives each distinct mark: the highest points value listed for it.
+fn table_scores(rows: &[&PointsInsert]) -> Vec<(f64, i32)> {
+    let mut best: HashMap<u64, (f64, i32)> = HashMap::new();
+    for row in rows {
+        let entry = best.entry(row.mark.to_bits()).or_insert((row.mark, row.points));
+        entry.1 = entry.1.max(row.points);
+    }
+    best.into_values().collect()
+}
+
+/// Solves a 3x3 linear system with Gaussian elimination and partial pivoting.
+fn solve(mut matrix: [[f64; 3]; 3], mut values: [f64; 3]) -> Option<[f64; 3]> {
+    for col in 0..3 {
+        let pivot = (col..3).max_by(|&i, &j| matrix[i][col].abs().total_cmp(&matrix[j][col].abs()))?;
+        if matrix[pivot][col].abs() < 1e-12 {
+            return None;
+        }
+        matrix.swap(col, pivot);
+        values.swap(col, pivot);
+
+        let pivot_row = matrix[col];
+        for row in col + 1..3 {
+            let factor = matrix[row][col] / pivot_row[col];
+            for (value, pivot_value) in matrix[row].iter_mut().zip(pivot_row).skip(col) {
+                *value -= factor * pivot_value;
+            }
+            values[row] -= factor * values[col];
+        }
+    }
+
+    let mut result = [0.0; 3];
+    for row in (0..3).rev() {
+        let known: f64 = (row + 1..3).map(|k| matrix[row][k] * result[k]).sum();
+        result[row] = (values[row] - known) / matrix[row][row];
+    }
+    Some(result)
+}
+
+/// A mark scored by the engine.
+#[derive(Debug, Clone, Serialize)]
+pub struct EngineScore {
+    pub category: String,
+    pub gender: String,
+    pub event: String,
+    /// The scoring table edition the model was fitted to.
+    pub edition: Option<i32>,
+    /// The mark after applying the World Athletics rounding rules.
+    pub mark: f64,
+    pub mark_display: String,
+    /// The mark in feet and inches for events measured in metres.
+    pub mark_imperial: Option<String>,
+    pub lower_is_better: bool,
+    pub timing: TimingMethod,
+    /// The seconds added to the submitted mark to convert a hand time, zero for automatic times.
+    pub timing_adjustment: f64,
+    pub track: TrackType,
+    /// The factor the mark was multiplied by to convert it to a standard banked track.
+    pub track_factor: f64,
+    /// `None` if the mark is below the lowest table entry.
+    pub points: Option<i32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Discrepancy {
+    pub category: String,
+    pub gender: String,
+    pub event: String,
+    pub mark: f64,
+    pub table_points: i32,
+    pub engine_points: Option<i32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EventVerification {
+    pub category: String,
+    pub gender: String,
+    pub event: String,
+    pub rows_checked: usize,
+    pub discrepancies: usize,
+    pub max_difference: i32,
+}
+
+/// The result of checking the engine against every stored table row.
+#[derive(Debug, Clone, Serialize)]
+pub struct VerificationReport {
+    pub rows_checked: usize,
+    pub discrepancies: usize,
+    pub max_difference: i32,
+    /// Events in the table the engine has no model for.
+    pub missing_events: Vec<String>,
+    pub events: Vec<EventVerification>,
+    /// The first discrepancies found, capped at [`MAX_DISCREPANCY_SAMPLES`].
+    pub samples: Vec<Discrepancy>,
+}
+
+/// Scores marks in-process from per-event curves fitted to the points table, without a database
+/// round trip.
+#[derive(Debug, Clone, Default)]
+pub struct ScoringEngine {
+    events: HashMap<EventKey, EventModel>,
+}
+
+impl ScoringEngine {
+    /// Fits a model for every event in the given table rows. Events with fewer than three distinct
+    /// marks cannot be fitted and are left out.
+    pub fn fit(rows: &[PointsInsert]) -> Self {
+        let mut events = HashMap::new();
+        for (key, event_rows) in group_by_event(rows) {
+            if let Some(model) = EventModel::fit(&event_rows) {
+                events.insert(key, model);
+            }
+        }
+        Self { events }
+    }
+
+    /// The fitted models, sorted by category, gender and event.
+    pub fn models(&self) -> Vec<&EventModel> {
+        let mut models: Vec<&EventModel> = self.events.values().collect();
+        models.sort_by(|a, b| (&a.category, &a.gender, &a.event).cmp(&(&b.category, &b.gender, &b.event)));
+        models
+    }
+
+    /// Scores a mark in seconds or metres, converting hand times and indoor times on non-standard
+    /// tracks first. Returns `None` if the engine has no model for the event.
+    pub fn score(
+        &self,
+        category: &Category,
+        gender: &str,
+        event: &str,
+        mark: f64,
+        timing: TimingMethod,
+        track: TrackType,
+    ) -> Option<EngineScore> {
+        let model = self.events.get(&EventKey::new(&category.to_string(), gender, event))?;
+        let timing_adjustment = timing.adjustment(event);
+        let track_factor = track.factor(category, event);
+        let mark = round_mark((mark + timing_adjustment) * track_factor, model.lower_is_better);
+
+        Some(EngineScore {
+            category: model.category.clone(),
+            gender: model.gender.clone(),
+            event: model.event.clone(),
+            edition: model.edition,
+            mark,
+            mark_display: format_mark(mark, model.lower_is_better),
+            mark_imperial: format_imperial_mark(mark, &model.event, model.lower_is_better),
+            lower_is_better: model.lower_is_better,
+            timing,
+            timing_adjustment,
+            track,
+            track_factor,
+            points: model.points(mark),
+        })
+    }
+
+    /// Checks the engine against every table row, comparing the engine's score for each row's mark
+    /// with the points the table gives that mark.
+    pub fn verify(&self, rows: &[PointsInsert]) -> VerificationReport {
+        let mut report = VerificationReport {
+            rows_checked: 0,
+            discrepancies: 0,
+            max_difference: 0,
+            missing_events: vec![],
+            events: vec![],
+            samples: vec![],
+        };
+
+        let mut groups: Vec<(EventKey, Vec<&PointsInsert>)> = group_by_event(rows).into_iter().collect();
+        groups.sort_by(|(a, _), (b, _)| (&a.category, &a.gender, &a.event).cmp(&(&b.category, &b.gender, &b.event)));
+
+        for (key, event_rows) in groups {
+            let first = event_rows[0];
+            let Some(model) = self.events.get(&key) else {
+                report.missing_events.push(format!("{} {} {}", first.category, first.gender, first.event));
+                continue;
+            };
+
+            let table_scores: HashMap<u64, i32> = table_scores(&event_rows)
+                .into_iter()
+                .map(|(mark, points)| (mark.to_bits(), points))
+                .collect();
+            let mut event_report = EventVerification {
+                category: first.category.clone(),
+                gender: first.gender.clone(),
+                event: first.event.clone(),
+                rows_checked: event_rows.len(),
+                discrepancies: 0,
+                max_difference: 0,
+            };
+
+            for row in &event_rows {
+                let table_points = table_scores[&row.mark.to_bits()];
+                let engine_points = model.points(round_mark(row.mark, model.lower_is_better));
+                if engine_points == Some(table_points) {
+                    continue;
+                }
+
+                let difference = (engine_points.unwrap_or(0) - table_points).abs();
+                event_report.discrepancies += 1;
+                event_report.max_difference = event_report.max_difference.max(difference);
+                if report.samples.len() < MAX_DISCREPANCY_SAMPLES {
+                    report.samples.push(Discrepancy {
+                        category: row.category.clone(),
+                        gender: row.gender.clone(),
+                        event: row.event.clone(),
+                        mark: row.mark,
+                        table_points,
+                        engine_points,
+                    });
+                }
+            }
+
+            report.rows_checked += event_report.rows_checked;
+            report.discrepancies += event_report.discrepancies;
+            report.max_difference = report.max_difference.max(event_report.max_difference);
+            report.events.push(event_report);
+        }
+
+        report
+    }
+}
+
+fn group_by_event(rows: &[PointsInsert]) -> HashMap<EventKey, Vec<&PointsInsert>> {
+    let mut groups: HashMap<EventKey, Vec<&PointsInsert>> = HashMap::new();
+    for row in rows {
+        groups
+            .entry(EventKey::new(&row.category, &row.gender, &row.event))
+            .or_default()
+            .push(row);
+    }
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A table following `a * |x - b|^2` in hundredths from `from` to `to`, like the World Athletics
+    /// tables, with points floored and rows below one point left out.
+    fn table(event: &str, a: f64, b: f64, from: i32, to: i32) -> Vec<PointsInsert> {
+        (from..=to)
+            .map(|hundredths| hundredths as f64 / 100.0)
+            .map(|mark| PointsInsert {
+                points: (a * (mark - b).powi(2)).floor() as i32,
+                gender: "Male".to_string(),
+                category: "Outdoor".to_string(),
+                event: event.to_string(),
+                mark,
+                edition: Some(2022),
+                ..Default::default()
+            })
+            .filter(|row| row.points >= 1)
+            .collect()
+    }
+
+    fn score(engine: &ScoringEngine, event: &str, mark: f64) -> Option<i32> {
+        engine
+            .score(&Category::Outdoor, "Male", event, mark, TimingMethod::default(), TrackType::default())?
+            .points
+    }
+
+    #[test]
+    fn solves_linear_systems() {
+        let matrix = [[2.0, 1.0, -1.0], [-3.0, -1.0, 2.0], [-2.0, 1.0, 2.0]];
+        let [x, y, z] = solve(matrix, [8.0, -11.0, -3.0]).unwrap();
+        assert!((x - 2.0).abs() < 1e-9 && (y - 3.0).abs() < 1e-9 && (z + 1.0).abs() < 1e-9);
+
+        assert!(solve([[1.0, 2.0, 3.0], [2.0, 4.0, 6.0], [0.0, 0.0, 1.0]], [1.0, 2.0, 3.0]).is_none());
+    }
+
+    #[test]
+    fn fits_a_track_table_to_within_a_point() {
+        let rows = table("100m", 24.63, 17.0, 950, 1600);
+        let engine = ScoringEngine::fit(&rows);
+        let model = engine.models()[0];
+        assert!(model.lower_is_better);
+        assert_eq!((model.best_mark, model.worst_mark), (9.5, 16.0));
+        assert_eq!(model.edition, Some(2022));
+
+        let report = engine.verify(&rows);
+        assert_eq!(report.rows_checked, rows.len());
+        assert!(report.max_difference <= 1, "{:?}", report.samples);
+        assert!(report.discrepancies * 20 < rows.len(), "{} discrepancies", report.discrepancies);
+    }
+
+    #[test]
+    fn fits_a_field_table_to_within_a_point() {
+        let rows = table("LJ", 23.6, 1.8, 500, 895);
+        let engine = ScoringEngine::fit(&rows);
+        assert!(!engine.models()[0].lower_is_better);
+
+        let report = engine.verify(&rows);
+        assert!(report.max_difference <= 1, "{:?}", report.samples);
+        assert!(report.discrepancies * 20 < rows.len(), "{} discrepancies", report.discrepancies);
+    }
+
+    #[test]
+    fn rounds_marks_before_scoring_them() {
+        let rows = table("100m", 24.63, 17.0, 950, 1600);
+        let engine = ScoringEngine::fit(&rows);
+        let points = |mark: f64| rows.iter().find(|r| r.mark == mark).unwrap().points;
+
+        for (entered, rounded) in [(10.001, 10.01), (10.01, 10.01), (12.345, 12.35)] {
+            let scored = score(&engine, "100m", entered).unwrap();
+            assert!((scored - points(rounded)).abs() <= 1, "{} scored {}", entered, scored);
+        }
+        // Aliases find the same model.
+        assert_eq!(score(&engine, "100 Metres", 10.01), score(&engine, "100m", 10.01));
+    }
+
+    #[test]
+    fn marks_off_the_table_are_capped_or_unscored() {
+        let rows = table("100m", 24.63, 17.0, 950, 1600);
+        let engine = ScoringEngine::fit(&rows);
+        let model = engine.models()[0];
+
+        assert_eq!(score(&engine, "100m", 9.2), Some(model.max_points));
+        assert_eq!(score(&engine, "100m", 16.5), None);
+        assert_eq!(score(&engine, "200m", 20.0), None);
+    }
+
+    #[test]
+    fn events_with_fewer_than_three_marks_are_not_fitted() {
+        let rows = table("100m", 24.63, 17.0, 1000, 1001);
+        assert!(ScoringEngine::fit(&rows).models().is_empty());
+        assert_eq!(ScoringEngine::fit(&rows).verify(&rows).missing_events, ["Outdoor Male 100m"]);
+    }
+}
@@ -0,0 +1,131 @@
+use std::{collections::HashMap, sync::OnceLock};
+
+/// An event under its canonical name, the World Athletics code used in the scoring tables, along
+/// with the other names it is known by. Spacing, case, punctuation and spelled out units such as
+/// "metres" or "hurdles" are already handled by [`normalize`], so aliases only list genuinely
+/// different names.
+#[derive(Debug, Clone, Copy)]
+pub struct EventDefinition {
+    pub name: &'static str,
+    pub aliases: &'static [&'static str],
+}
+
+const fn event(name: &'static str, aliases: &'static [&'static str]) -> EventDefinition {
+    EventDefinition { name, aliases }
+}
+
+pub const EVENTS: &[EventDefinition] = &[
+    event("50m", &[]),
+    event("55m", &[]),
+    event("60m", &[]),
+    event("100m", &[]),
+    event("200m", &[]),
+    event("300m", &[]),
+    event("400m", &[]),
+    event("500m", &[]),
+    event("600m", &[]),
+    event("800m", &[]),
+    event("1000m", &[]),
+    event("1500m", &[]),
+    event("Mile", &["1 Mile", "One Mile"]),
+    event("2000m", &[]),
+    event("3000m", &[]),
+    event("2 Miles", &["Two Miles", "2 Mile"]),
+    event("5000m", &[]),
+    event("10000m", &[]),
+    event("50mH", &[]),
+    event("55mH", &[]),
+    event("60mH", &[]),
+    event("100mH", &[]),
+    event("110mH", &[]),
+    event("300mH", &[]),
+    event("400mH", &[]),
+    event("2000mSC", &["2000m Steeple"]),
+    event("3000mSC", &["3000m Steeple", "Steeplechase"]),
+    event("HJ", &["High Jump"]),
+    event("PV", &["Pole Vault"]),
+    event("LJ", &["Long Jump"]),
+    event("TJ", &["Triple Jump"]),
+    event("SP", &["Shot Put", "Shot"]),
+    event("DT", &["Discus Throw", "Discus"]),
+    event("HT", &["Hammer Throw", "Hammer"]),
+    event("JT", &["Javelin Throw", "Javelin"]),
+    event("Pentathlon", &["Pent"]),
+    event("Heptathlon", &["Hept"]),
+    event("Decathlon", &["Dec"]),
+    event("5km", &["5k", "Road 5km"]),
+    event("10km", &["10k", "Road 10km"]),
+    event("15km", &["15k", "Road 15km"]),
+    event("20km", &["20k", "Road 20km"]),
+    event("HM", &["Half Marathon", "Half"]),
+    event("Marathon", &["Mar"]),
+    event("100km", &["100k"]),
+    event("3000mW", &[]),
+    event("5000mW", &[]),
+    event("10000mW", &[]),
+    event("20000mW", &[]),
+    event("10kmW", &[]),
+    event("20kmW", &[]),
+    event("35kmW", &[]),
+    event("50kmW", &[]),
+    event("4x100m", &["4x100"]),
+    event("4x200m", &["4x200"]),
+    event("4x400m", &["4x400"]),
+];
+
+/// Reduces an event name to the form names are compared in: lowercase, without spaces or
+/// punctuation, and with spelled out units replaced by their abbreviations.
+pub fn normalize(name: &str) -> String {
+    let mut key: String = name
+        .to_lowercase()
+        .chars()
+        .filter(|c| !c.is_whitespace() && !matches!(c, '.' | ',' | '-' | '_' | '\''))
+        .collect();
+
+    for (long, short) in [
+        ("kilometres", "km"),
+        ("kilometers", "km"),
+        ("kilometre", "km"),
+        ("kilometer", "km"),
+        ("metres", "m"),
+        ("meters", "m"),
+        ("metre", "m"),
+        ("meter", "m"),
+        ("hurdles", "h"),
+        ("steeplechase", "sc"),
+        ("racewalk", "w"),
+        ("walk", "w"),
+    ] {
+        key = key.replace(long, short);
+    }
+    key
+}
+
+fn registry() -> &'static HashMap<String, &'static str> {
+    static REGISTRY: OnceLock<HashMap<String, &'static str>> = OnceLock::new();
+    REGISTRY.get_or_init(|| {
+        EVENTS
+            .iter()
+            .flat_map(|e| std::iter::once(e.name).chain(e.aliases.iter().copied()).map(|n| (normalize(n), e.name)))
+            .collect()
+    })
+}
+
+/// The canonical name of an event, `None` if the name is not in the registry.
+pub fn canonical_event(name: &str) -> Option<&'static str> {
+    registry().get(&normalize(name)).copied()
+}
+
+/// Resolves an event name to its canonical name. Names outside the registry are kept as given,
+/// trimmed, so events only found in newly loaded tables still work.
+pub fn resolve_event(name: &str) -> String {
+    canonical_event(name).map(str::to_string).unwrap_or_else(|| name.trim().to_string())
+}
+
+/// Whether lower marks are better in an event: true for races and walks, false for jumps, throws and
+/// combined events. `None` for events outside the registry.
+pub fn lower_is_better(name: &str) -> Option<bool> {
+    canonical_event(name).map(|event| {
+        !matches!(event, "HJ" | "PV" | "LJ" | "TJ" | "SP" | "DT" | "HT" | "JT" | "Pentathlon" | "Heptathlon" | "Decathlon")
+    })
+}
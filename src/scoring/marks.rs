@@ -0,0 +1,304 @@
+use std::fmt;
+
+/// Errors returned when a mark cannot be parsed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MarkParseError {
+    Empty,
+    InvalidNumber(String),
+    ComponentOutOfRange(String),
+    TooManyComponents(String),
+    InchesOutOfRange(String),
+    NotPositive(String),
+}
+
+impl fmt::Display for MarkParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MarkParseError::Empty => write!(f, "mark is empty"),
+            MarkParseError::InvalidNumber(mark) => write!(f, "'{}' is not a valid mark", mark),
+            MarkParseError::ComponentOutOfRange(mark) => {
+                write!(f, "'{}' has minutes or seconds outside 0-59", mark)
+            }
+            MarkParseError::TooManyComponents(mark) => {
+                write!(f, "'{}' has too many components, expected h:mm:ss, m:ss or ss", mark)
+            }
+            MarkParseError::InchesOutOfRange(mark) => write!(f, "'{}' has 12 or more inches", mark),
+            MarkParseError::NotPositive(mark) => write!(f, "{} is not a positive mark", mark),
+        }
+    }
+}
+
+impl std::error::Error for MarkParseError {}
+
+/// Metres in an inch, the exact conversion used for imperial marks.
+pub const METRES_PER_INCH: f64 = 0.0254;
+
+/// Checks a mark can be scored. Times and distances are positive, so zero, negative and non-finite
+/// marks are rejected.
+pub fn check_mark(mark: f64) -> Result<f64, MarkParseError> {
+    if mark.is_finite() && mark > 0.0 {
+        Ok(mark)
+    } else {
+        Err(MarkParseError::NotPositive(mark.to_string()))
+    }
+}
+
+/// Parses a mark to be scored, see [`parse_mark`] and [`check_mark`].
+pub fn parse_scorable_mark(input: &str) -> Result<f64, MarkParseError> {
+    check_mark(parse_mark(input)?)
+}
+
+/// Parses a mark into seconds or metres.
+///
+/// Plain numbers (`10.44`, `7.12`) are returned as is, and distances may carry a metre unit
+/// (`7.12m`). Clock-style times (`3:45.12`, `2:05:30`) are converted to seconds; only the last
+/// component may have a fraction and every component after the first must be below 60. Feet and
+/// inches (`23' 4.5"`, `23ft 4.5in`) are converted to metres, see [`parse_imperial`].
+pub fn parse_mark(input: &str) -> Result<f64, MarkParseError> {
+    let mark = input.trim();
+    if mark.is_empty() {
+        return Err(MarkParseError::Empty);
+    }
+    if let Some(metres) = parse_imperial(mark)? {
+        return Ok(metres);
+    }
+    let mark = match mark.strip_suffix(['m', 'M']) {
+        Some(metres) if !metres.contains(':') => metres.trim_end(),
+        _ => mark,
+    };
+
+    let components: Vec<&str> = mark.split(':').collect();
+    if components.len() > 3 {
+        return Err(MarkParseError::TooManyComponents(mark.to_string()));
+    }
+
+    let last = components.len() - 1;
+    let mut total = 0.0;
+    for (index, component) in components.iter().enumerate() {
+        let valid_chars = if index == last {
+            component.chars().all(|c| c.is_ascii_digit() || c == '.')
+        } else {
+            component.chars().all(|c| c.is_ascii_digit())
+        };
+        if component.is_empty() || !valid_chars {
+            return Err(MarkParseError::InvalidNumber(mark.to_string()));
+        }
+
+        let value: f64 = component
+            .parse()
+            .map_err(|_| MarkParseError::InvalidNumber(mark.to_string()))?;
+        if index > 0 && value >= 60.0 {
+            return Err(MarkParseError::ComponentOutOfRange(mark.to_string()));
+        }
+
+        total = total * 60.0 + value;
+    }
+
+    Ok(total)
+}
+
+/// Parses a distance in feet and inches such as `23' 4.5"`, `23'`, `4.5"` or `23ft 4.5in`.
+///
+/// Imperial marks are converted to metres and rounded down to the centimetre, the official rule
+/// for converting imperial measurements. Returns `None` when the mark has no imperial units.
+pub fn parse_imperial(input: &str) -> Result<Option<f64>, MarkParseError> {
+    let invalid = || MarkParseError::InvalidNumber(input.trim().to_string());
+    let mark = input
+        .trim()
+        .to_lowercase()
+        .replace(['\u{2019}', '\u{2032}'], "'")
+        .replace(['\u{201d}', '\u{2033}'], "\"")
+        .replace("''", "\"")
+        .replace("feet", "'")
+        .replace("foot", "'")
+        .replace("ft", "'")
+        .replace("inches", "\"")
+        .replace("inch", "\"")
+        .replace("in", "\"");
+    if !mark.contains(['\'', '"']) {
+        return Ok(None);
+    }
+
+    let (feet, inches) = match mark.split_once('\'') {
+        Some((feet, inches)) => (feet.trim(), inches.trim()),
+        None => ("", mark.as_str()),
+    };
+    let inches = inches.strip_suffix('"').unwrap_or(inches).trim();
+    let number = |value: &str, fraction: bool| -> Result<f64, MarkParseError> {
+        let valid = value.chars().all(|c| c.is_ascii_digit() || (fraction && c == '.'));
+        if value.is_empty() || !valid {
+            return Err(invalid());
+        }
+        value.parse().map_err(|_| invalid())
+    };
+
+    let feet = if feet.is_empty() { 0.0 } else { number(feet, false)? };
+    let inches = if inches.is_empty() { 0.0 } else { number(inches, true)? };
+    if feet > 0.0 && inches >= 12.0 {
+        return Err(MarkParseError::InchesOutOfRange(input.trim().to_string()));
+    }
+    if feet == 0.0 && inches == 0.0 && !mark.chars().any(|c| c.is_ascii_digit()) {
+        return Err(invalid());
+    }
+
+    Ok(Some(round_mark((feet * 12.0 + inches) * METRES_PER_INCH, false)))
+}
+
+/// Formats a distance in metres as feet and inches, rounded down to the quarter inch: `23' 4.25"`.
+pub fn format_imperial(metres: f64) -> String {
+    // Round away float noise first so an exact quarter inch is not pushed down.
+    let quarters = ((metres / METRES_PER_INCH * 4.0 * 1e6).round() / 1e6).floor() as i64;
+    let (feet, inches) = (quarters / 48, (quarters % 48) as f64 / 4.0);
+    format!("{}' {}\"", feet, inches)
+}
+
+/// Formats a mark in feet and inches when the event is measured in metres, `None` for timed events
+/// and the points totals of combined events.
+pub fn format_imperial_mark(mark: f64, event: &str, lower_is_better: bool) -> Option<String> {
+    (!lower_is_better && !event.to_lowercase().ends_with("athlon")).then(|| format_imperial(mark))
+}
+
+/// Formats seconds as a clock-style time: `10.44`, `3:45.12` or `2:05:30`.
+///
+/// Hundredths are dropped from times of an hour or more when they are zero, matching how road
+/// results are published.
+pub fn format_time(seconds: f64) -> String {
+    let hundredths = (seconds * 100.0).round() as i64;
+    let fraction = hundredths % 100;
+    let whole = hundredths / 100;
+    let (hours, minutes, secs) = (whole / 3600, whole / 60 % 60, whole % 60);
+
+    if hours > 0 && fraction == 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, secs)
+    } else if hours > 0 {
+        format!("{}:{:02}:{:02}.{:02}", hours, minutes, secs, fraction)
+    } else if minutes > 0 {
+        format!("{}:{:02}.{:02}", minutes, secs, fraction)
+    } else {
+        format!("{}.{:02}", secs, fraction)
+    }
+}
+
+/// Formats a mark for display, as a clock-style time for timed events and in metres otherwise.
+pub fn format_mark(mark: f64, lower_is_better: bool) -> String {
+    if lower_is_better {
+        format_time(mark)
+    } else {
+        format!("{:.2}", mark)
+    }
+}
+
+/// Rounds a mark the way World Athletics rounds results: times up to the next hundredth and
+/// distances down to the centimetre.
+pub fn round_mark(mark: f64, lower_is_better: bool) -> f64 {
+    // Round away float noise first so 10.45 stored as 10.4500000001 is not pushed up to 10.46.
+    let hundredths = (mark * 100.0 * 1e6).round() / 1e6;
+    if lower_is_better {
+        hundredths.ceil() / 100.0
+    } else {
+        hundredths.floor() / 100.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-9, "{} != {}", actual, expected);
+    }
+
+    #[test]
+    fn parses_plain_numbers_and_metres() {
+        assert_close(parse_mark("10.44").unwrap(), 10.44);
+        assert_close(parse_mark(" 7.12m ").unwrap(), 7.12);
+        assert_close(parse_mark("7.12 M").unwrap(), 7.12);
+    }
+
+    #[test]
+    fn parses_clock_style_times() {
+        assert_close(parse_mark("3:45.12").unwrap(), 225.12);
+        assert_close(parse_mark("59:59").unwrap(), 3599.0);
+        assert_close(parse_mark("2:05:30").unwrap(), 7530.0);
+        assert_close(parse_mark("1:02:03.45").unwrap(), 3723.45);
+    }
+
+    #[test]
+    fn rejects_malformed_marks() {
+        assert_eq!(parse_mark("  "), Err(MarkParseError::Empty));
+        assert_eq!(parse_mark("abc"), Err(MarkParseError::InvalidNumber("abc".to_string())));
+        assert_eq!(parse_mark("-5"), Err(MarkParseError::InvalidNumber("-5".to_string())));
+        assert_eq!(parse_mark("10..4"), Err(MarkParseError::InvalidNumber("10..4".to_string())));
+        assert_eq!(parse_mark("1.5:30"), Err(MarkParseError::InvalidNumber("1.5:30".to_string())));
+        assert_eq!(parse_mark("3:"), Err(MarkParseError::InvalidNumber("3:".to_string())));
+        assert_eq!(parse_mark("1:60"), Err(MarkParseError::ComponentOutOfRange("1:60".to_string())));
+        assert_eq!(parse_mark("1:2:3:4"), Err(MarkParseError::TooManyComponents("1:2:3:4".to_string())));
+    }
+
+    #[test]
+    fn zero_parses_but_cannot_be_scored() {
+        assert_eq!(parse_mark("0"), Ok(0.0));
+        assert_eq!(parse_mark("0:00"), Ok(0.0));
+        assert_eq!(parse_scorable_mark("0"), Err(MarkParseError::NotPositive("0".to_string())));
+        assert!(check_mark(-1.0).is_err());
+        assert!(check_mark(f64::NAN).is_err());
+        assert!(check_mark(f64::INFINITY).is_err());
+        assert_eq!(check_mark(10.44), Ok(10.44));
+    }
+
+    #[test]
+    fn parses_feet_and_inches() {
+        assert_eq!(parse_mark("23' 4.5\""), Ok(7.12));
+        assert_eq!(parse_imperial("23ft 4.5in"), Ok(Some(7.12)));
+        assert_eq!(parse_imperial("23 feet 4.5 inches"), Ok(Some(7.12)));
+        assert_eq!(parse_imperial("23\u{2019} 4.5\u{201d}"), Ok(Some(7.12)));
+        assert_eq!(parse_imperial("23'"), Ok(Some(7.01)));
+        assert_eq!(parse_imperial("4.5\""), Ok(Some(0.11)));
+        assert_eq!(parse_imperial("7.12"), Ok(None));
+    }
+
+    #[test]
+    fn rejects_malformed_imperial_marks() {
+        assert_eq!(parse_imperial("23' 12\""), Err(MarkParseError::InchesOutOfRange("23' 12\"".to_string())));
+        assert_eq!(parse_imperial("'"), Err(MarkParseError::InvalidNumber("'".to_string())));
+        assert_eq!(parse_imperial("23.5' 4\""), Err(MarkParseError::InvalidNumber("23.5' 4\"".to_string())));
+    }
+
+    #[test]
+    fn imperial_marks_round_trip() {
+        for metres in [7.62, 2.54, 15.24, 0.0] {
+            assert_eq!(parse_imperial(&format_imperial(metres)), Ok(Some(metres)), "{}", format_imperial(metres));
+        }
+        // Converting to metres rounds down to the centimetre, and back down to the quarter inch.
+        let metres = parse_imperial("23' 4.5\"").unwrap().unwrap();
+        assert_eq!(format_imperial(metres), "23' 4.25\"");
+        assert_eq!(format_imperial(7.62), "25' 0\"");
+    }
+
+    #[test]
+    fn formats_times() {
+        assert_eq!(format_time(10.44), "10.44");
+        assert_eq!(format_time(9.5), "9.50");
+        assert_eq!(format_time(60.0), "1:00.00");
+        assert_eq!(format_time(225.12), "3:45.12");
+        assert_eq!(format_time(7530.0), "2:05:30");
+        assert_eq!(format_time(3723.45), "1:02:03.45");
+    }
+
+    #[test]
+    fn formats_marks_by_event() {
+        assert_eq!(format_mark(225.12, true), "3:45.12");
+        assert_eq!(format_mark(7.1, false), "7.10");
+        assert_eq!(format_imperial_mark(7.62, "LJ", false), Some("25' 0\"".to_string()));
+        assert_eq!(format_imperial_mark(10.44, "100m", true), None);
+        assert_eq!(format_imperial_mark(8000.0, "Decathlon", false), None);
+    }
+
+    #[test]
+    fn rounds_times_up_and_distances_down() {
+        assert_eq!(round_mark(10.441, true), 10.45);
+        assert_eq!(round_mark(10.45, true), 10.45);
+        assert_eq!(round_mark(7.129, false), 7.12);
+        assert_eq!(round_mark(7.12, false), 7.12);
+    }
+}
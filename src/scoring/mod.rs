@@ -0,0 +1,12 @@
+pub mod marks;
+pub mod combined_events;
+pub mod engine;
+pub mod edition_diff;
+pub mod events;
+pub mod timing;
+pub mod wind;
+pub mod age_grading;
+pub mod track;
+pub mod seasons;
+pub mod progression;
+pub mod world_rankings;
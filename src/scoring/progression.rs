@@ -0,0 +1,128 @@
+use chrono::NaiveDate;
+use serde::Serialize;
+
+use crate::models::{
+    iaaf_points::Category,
+    performance::{Aggregation, Performance},
+};
+
+use super::seasons::Seasons;
+
+/// One performance in a progression series.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProgressionPoint {
+    /// The month (`2026-05`) or season (`2026`) the performance is the best of, `None` when the series
+    /// is not aggregated.
+    pub period: Option<String>,
+    pub date: NaiveDate,
+    pub performance_id: i32,
+    pub competition: Option<String>,
+    pub mark: f64,
+    pub mark_display: String,
+    pub scored_mark: Option<f64>,
+    pub points: Option<i32>,
+    pub wind_legal: bool,
+}
+
+/// How a user's performances in one event progress over time.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProgressionSeries {
+    pub category: String,
+    pub gender: String,
+    /// The canonical name of the event, see [`crate::scoring::events`].
+    pub event: String,
+    pub lower_is_better: bool,
+    /// Oldest first.
+    pub series: Vec<ProgressionPoint>,
+}
+
+/// Groups performances into one time-ordered series per event, optionally keeping only the best
+/// performance in each month or season.
+///
+/// Performances without a date cannot be placed in time and are left out. When aggregating, the best
+/// performance is picked the way personal bests are: on the scored mark, ignoring wind-aided marks,
+/// with ties going to the earlier performance.
+pub fn progression(
+    mut performances: Vec<Performance>,
+    aggregate: Option<Aggregation>,
+    seasons: &Seasons,
+) -> Vec<ProgressionSeries> {
+    performances.retain(|p| p.date.is_some());
+    performances.sort_by(|a, b| {
+        (&a.category, &a.gender, &a.event, a.date, a.id).cmp(&(&b.category, &b.gender, &b.event, b.date, b.id))
+    });
+
+    let mut series: Vec<ProgressionSeries> = vec![];
+    for performance in performances {
+        let same_event = series.last().is_some_and(|s| {
+            s.category == performance.category && s.gender == performance.gender && s.event == performance.event
+        });
+        if !same_event {
+            series.push(ProgressionSeries {
+                category: performance.category.clone(),
+                gender: performance.gender.clone(),
+                event: performance.event.clone(),
+                lower_is_better: performance.lower_is_better,
+                series: vec![],
+            });
+        }
+        let Some(event) = series.last_mut() else { continue };
+
+        let Some(aggregate) = aggregate else {
+            event.series.push(point(&performance, None));
+            continue;
+        };
+        if performance.wind.wind_aided {
+            continue;
+        }
+        let period = period(&performance, aggregate, seasons);
+        // Performances are in date order and periods are contiguous, so a period's performances are
+        // next to each other.
+        match event.series.last_mut() {
+            Some(best) if best.period.as_ref() == Some(&period) => {
+                if is_better(&performance, best, event.lower_is_better) {
+                    *best = point(&performance, Some(period));
+                }
+            }
+            _ => event.series.push(point(&performance, Some(period))),
+        }
+    }
+
+    series.retain(|s| !s.series.is_empty());
+    series
+}
+
+fn period(performance: &Performance, aggregate: Aggregation, seasons: &Seasons) -> String {
+    let date = performance.date.unwrap_or_default();
+    match aggregate {
+        Aggregation::Month => date.format("%Y-%m").to_string(),
+        Aggregation::Season => {
+            let category = performance.category.parse().unwrap_or(Category::Outdoor);
+            seasons.for_category(&category).season_of(date).to_string()
+        }
+    }
+}
+
+fn is_better(performance: &Performance, best: &ProgressionPoint, lower_is_better: bool) -> bool {
+    let (mark, best_mark) =
+        (performance.scored_mark.unwrap_or(performance.mark), best.scored_mark.unwrap_or(best.mark));
+    if lower_is_better {
+        mark < best_mark
+    } else {
+        mark > best_mark
+    }
+}
+
+fn point(performance: &Performance, period: Option<String>) -> ProgressionPoint {
+    ProgressionPoint {
+        period,
+        date: performance.date.unwrap_or_default(),
+        performance_id: performance.id,
+        competition: performance.competition.clone(),
+        mark: performance.mark,
+        mark_display: performance.mark_display.clone(),
+        scored_mark: performance.scored_mark,
+        points: performance.points,
+        wind_legal: performance.wind.wind_legal,
+    }
+}
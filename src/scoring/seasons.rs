@@ -0,0 +1,175 @@
+use std::{fmt, str::FromStr};
+
+use chrono::{Datelike, NaiveDate};
+
+use crate::models::iaaf_points::Category;
+
+/// The day of the year a season starts on. Seasons run for a year from that day and are named after
+/// the calendar year most of the season falls in, so with indoor seasons starting on 1 November the
+/// 2026 indoor season runs from 1 November 2025 to 31 October 2026.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SeasonStart {
+    month: u32,
+    day: u32,
+}
+
+impl SeasonStart {
+    /// Outdoor seasons follow the calendar year.
+    pub const OUTDOOR: SeasonStart = SeasonStart { month: 1, day: 1 };
+    /// Indoor seasons start in November and take in the winter.
+    pub const INDOOR: SeasonStart = SeasonStart { month: 11, day: 1 };
+
+    /// The season start in a year, `None` when the year is out of range.
+    fn in_year(self, year: i32) -> Option<NaiveDate> {
+        NaiveDate::from_ymd_opt(year, self.month, self.day)
+    }
+
+    /// Whether a season starts in the year it is named after rather than the year before.
+    fn starts_in_named_year(self) -> bool {
+        self.month <= 6
+    }
+
+    /// The season a date falls in.
+    pub fn season_of(self, date: NaiveDate) -> i32 {
+        let start = self.in_year(date.year()).expect("season start is a valid day in every year");
+        let start_year = if date >= start { date.year() } else { date.year() - 1 };
+        if self.starts_in_named_year() {
+            start_year
+        } else {
+            start_year + 1
+        }
+    }
+
+    /// The first and last day of a season, `None` when the season is too far in the past or future
+    /// to have dates.
+    pub fn dates(self, season: i32) -> Option<(NaiveDate, NaiveDate)> {
+        let start_year = if self.starts_in_named_year() { season } else { season.checked_sub(1)? };
+        let end = self.in_year(start_year.checked_add(1)?)?.pred_opt()?;
+        Some((self.in_year(start_year)?, end))
+    }
+}
+
+impl fmt::Display for SeasonStart {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:02}-{:02}", self.month, self.day)
+    }
+}
+
+impl FromStr for SeasonStart {
+    type Err = String;
+
+    /// Parses a `MM-DD` day such as `11-01`. 29 February is rejected as it is not in every year.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid season start '{}', expected a day of the year as MM-DD", s);
+        let (month, day) = s.trim().split_once('-').ok_or_else(invalid)?;
+        let (month, day) = (month.parse().map_err(|_| invalid())?, day.parse().map_err(|_| invalid())?);
+        NaiveDate::from_ymd_opt(2023, month, day).ok_or_else(invalid)?;
+        Ok(SeasonStart { month, day })
+    }
+}
+
+/// When the indoor and outdoor seasons start, set with the `INDOOR_SEASON_START` and
+/// `OUTDOOR_SEASON_START` environment variables.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Seasons {
+    pub outdoor: SeasonStart,
+    pub indoor: SeasonStart,
+}
+
+impl Default for Seasons {
+    fn default() -> Self {
+        Self { outdoor: SeasonStart::OUTDOOR, indoor: SeasonStart::INDOOR }
+    }
+}
+
+impl Seasons {
+    pub fn for_category(&self, category: &Category) -> SeasonStart {
+        match category {
+            Category::Indoor => self.indoor,
+            Category::Outdoor => self.outdoor,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn indoor_seasons_are_named_after_the_year_they_end_in() {
+        assert_eq!(SeasonStart::INDOOR.dates(2026), Some((day(2025, 11, 1), day(2026, 10, 31))));
+        assert_eq!(SeasonStart::INDOOR.season_of(day(2025, 10, 31)), 2025);
+        assert_eq!(SeasonStart::INDOOR.season_of(day(2025, 11, 1)), 2026);
+        assert_eq!(SeasonStart::INDOOR.season_of(day(2026, 10, 31)), 2026);
+        assert_eq!(SeasonStart::INDOOR.season_of(day(2026, 11, 1)), 2027);
+    }
+
+    #[test]
+    fn outdoor_seasons_follow_the_calendar_year() {
+        assert_eq!(SeasonStart::OUTDOOR.dates(2026), Some((day(2026, 1, 1), day(2026, 12, 31))));
+        assert_eq!(SeasonStart::OUTDOOR.season_of(day(2025, 12, 31)), 2025);
+        assert_eq!(SeasonStart::OUTDOOR.season_of(day(2026, 1, 1)), 2026);
+    }
+
+    #[test]
+    fn seasons_starting_in_the_first_half_are_named_after_their_start() {
+        let start: SeasonStart = "04-01".parse().unwrap();
+        assert_eq!(start.dates(2026), Some((day(2026, 4, 1), day(2027, 3, 31))));
+        assert_eq!(start.season_of(day(2026, 3, 31)), 2025);
+        assert_eq!(start.season_of(day(2026, 4, 1)), 2026);
+
+        let start: SeasonStart = "07-01".parse().unwrap();
+        assert_eq!(start.dates(2026), Some((day(2025, 7, 1), day(2026, 6, 30))));
+        assert_eq!(start.season_of(day(2025, 7, 1)), 2026);
+    }
+
+    #[test]
+    fn seasons_ending_in_february_take_in_leap_days() {
+        let start: SeasonStart = "03-01".parse().unwrap();
+        assert_eq!(start.dates(2023), Some((day(2023, 3, 1), day(2024, 2, 29))));
+        assert_eq!(start.dates(2024), Some((day(2024, 3, 1), day(2025, 2, 28))));
+        assert_eq!(start.season_of(day(2024, 2, 29)), 2023);
+    }
+
+    #[test]
+    fn every_day_of_a_season_is_in_it() {
+        for start in [SeasonStart::OUTDOOR, SeasonStart::INDOOR, "03-01".parse().unwrap(), "09-15".parse().unwrap()] {
+            let (first, last) = start.dates(2026).unwrap();
+            assert_eq!(start.season_of(first), 2026, "{}", start);
+            assert_eq!(start.season_of(last), 2026, "{}", start);
+            assert_eq!(start.season_of(first.pred_opt().unwrap()), 2025, "{}", start);
+            assert_eq!(start.season_of(last.succ_opt().unwrap()), 2027, "{}", start);
+        }
+    }
+
+    #[test]
+    fn seasons_out_of_the_date_range_have_no_dates() {
+        for start in [SeasonStart::OUTDOOR, SeasonStart::INDOOR] {
+            assert_eq!(start.dates(300_000), None, "{}", start);
+            assert_eq!(start.dates(-300_000), None, "{}", start);
+            assert_eq!(start.dates(i32::MIN), None, "{}", start);
+            assert_eq!(start.dates(i32::MAX), None, "{}", start);
+        }
+    }
+
+    #[test]
+    fn parses_and_displays_season_starts() {
+        assert_eq!("11-01".parse(), Ok(SeasonStart::INDOOR));
+        assert_eq!(" 1-1 ".parse(), Ok(SeasonStart::OUTDOOR));
+        assert_eq!(SeasonStart::INDOOR.to_string(), "11-01");
+        for invalid in ["02-29", "13-01", "04-31", "1101", "", "aa-bb"] {
+            assert!(invalid.parse::<SeasonStart>().is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn picks_the_season_start_for_a_category() {
+        let seasons = Seasons::default();
+        assert_eq!(seasons.for_category(&Category::Indoor), SeasonStart::INDOOR);
+        assert_eq!(seasons.for_category(&Category::Outdoor), SeasonStart::OUTDOOR);
+    }
+}
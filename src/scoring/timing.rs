@@ -0,0 +1,55 @@
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use super::events::canonical_event;
+
+/// How a timed mark was measured.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum TimingMethod {
+    /// Fully automatic timing, what the scoring tables are based on.
+    #[default]
+    Fat,
+    /// Stopwatch timing, converted to an equivalent automatic time before scoring.
+    Hand,
+}
+
+impl fmt::Display for TimingMethod {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TimingMethod::Fat => write!(f, "fat"),
+            TimingMethod::Hand => write!(f, "hand"),
+        }
+    }
+}
+
+impl FromStr for TimingMethod {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "fat" => Ok(TimingMethod::Fat),
+            "hand" => Ok(TimingMethod::Hand),
+            _ => Err(format!("unknown timing method '{}', expected fat or hand", s)),
+        }
+    }
+}
+
+impl TimingMethod {
+    /// The seconds added to a mark in an event to make it comparable with fully automatic times.
+    ///
+    /// Hand times react faster than automatic timing, so the standard conversions add 0.24s for
+    /// events up to 200m, hurdles included, and 0.14s for 300m and 400m. Longer and non-timed events
+    /// are not adjusted.
+    pub fn adjustment(self, event: &str) -> f64 {
+        if self == TimingMethod::Fat {
+            return 0.0;
+        }
+        match canonical_event(event) {
+            Some("50m" | "55m" | "60m" | "100m" | "200m" | "50mH" | "55mH" | "60mH" | "100mH" | "110mH") => 0.24,
+            Some("300m" | "400m" | "300mH" | "400mH") => 0.14,
+            _ => 0.0,
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::iaaf_points::Category;
+
+use super::events::canonical_event;
+
+/// The kind of indoor track a mark was set on.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum TrackType {
+    /// A standard 200m banked track, what the indoor tables are based on.
+    #[default]
+    Banked,
+    /// A flat track of 200m or less, slower through its tighter, unbanked bends.
+    Flat,
+    /// A track longer than 200m, faster over longer races as it has fewer bends per lap.
+    Oversized,
+}
+
+impl fmt::Display for TrackType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TrackType::Banked => write!(f, "banked"),
+            TrackType::Flat => write!(f, "flat"),
+            TrackType::Oversized => write!(f, "oversized"),
+        }
+    }
+}
+
+impl FromStr for TrackType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "banked" => Ok(TrackType::Banked),
+            "flat" => Ok(TrackType::Flat),
+            "oversized" => Ok(TrackType::Oversized),
+            _ => Err(format!("unknown track type '{}', expected banked, flat or oversized", s)),
+        }
+    }
+}
+
+/// Conversion factors from a flat track to a banked track, by canonical event.
+const FLAT_FACTORS: &[(&str, f64)] = &[
+    ("200m", 0.9876),
+    ("300m", 0.9902),
+    ("400m", 0.9886),
+    ("500m", 0.9910),
+    ("600m", 0.9922),
+    ("800m", 0.9936),
+    ("1000m", 0.9944),
+    ("1500m", 0.9961),
+    ("Mile", 0.9961),
+    ("3000m", 0.9975),
+    ("2 Miles", 0.9980),
+    ("5000m", 0.9983),
+    ("4x200m", 0.9876),
+    ("4x400m", 0.9886),
+];
+
+/// Conversion factors from an oversized track to a banked track, by canonical event.
+const OVERSIZED_FACTORS: &[(&str, f64)] = &[
+    ("800m", 1.0035),
+    ("1000m", 1.0040),
+    ("1500m", 1.0045),
+    ("Mile", 1.0045),
+    ("3000m", 1.0050),
+    ("2 Miles", 1.0050),
+    ("5000m", 1.0050),
+];
+
+impl TrackType {
+    /// The factor an indoor time on this track is multiplied by to give the equivalent time on a
+    /// standard banked track.
+    ///
+    /// Races run on the straight, field events and outdoor marks are not converted and use 1.0, as
+    /// does any event the published conversion tables do not cover.
+    pub fn factor(self, category: &Category, event: &str) -> f64 {
+        let factors = match (category, self) {
+            (Category::Indoor, TrackType::Flat) => FLAT_FACTORS,
+            (Category::Indoor, TrackType::Oversized) => OVERSIZED_FACTORS,
+            _ => return 1.0,
+        };
+        canonical_event(event)
+            .and_then(|event| factors.iter().find(|(name, _)| *name == event))
+            .map_or(1.0, |(_, factor)| *factor)
+    }
+}
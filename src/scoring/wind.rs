@@ -0,0 +1,107 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::iaaf_points::Category;
+
+use super::events::canonical_event;
+
+/// The strongest following wind, in metres per second, allowed for a mark to count for records.
+pub const WIND_LIMIT: f64 = 2.0;
+
+/// Whether wind is measured for an event. Only outdoor sprints up to 200m, the sprint hurdles and
+/// the horizontal jumps are wind assisted.
+pub fn wind_applies(category: &Category, event: &str) -> bool {
+    matches!(category, Category::Outdoor)
+        && matches!(canonical_event(event), Some("100m" | "200m" | "100mH" | "110mH" | "LJ" | "TJ"))
+}
+
+/// The wind reading for a mark and whether it makes the mark legal for records.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub struct WindStatus {
+    /// The wind in metres per second, positive for a following wind.
+    pub wind: Option<f64>,
+    pub wind_applicable: bool,
+    /// The wind was above [`WIND_LIMIT`].
+    pub wind_aided: bool,
+    /// The mark is legal for records as far as the wind goes. Marks in wind assisted events with no
+    /// reading are not legal.
+    pub wind_legal: bool,
+    /// The event is wind assisted but no reading was given.
+    pub wind_missing: bool,
+}
+
+/// Rounds a wind reading up to the next tenth of a metre per second, the way readings are reported.
+pub fn round_wind(wind: f64) -> f64 {
+    // Round away float noise first so a reading of 2.0 stays at 2.0.
+    ((wind * 10.0 * 1e6).round() / 1e6).ceil() / 10.0
+}
+
+impl WindStatus {
+    pub fn new(category: &Category, event: &str, wind: Option<f64>) -> Self {
+        let wind = wind.map(round_wind);
+        if !wind_applies(category, event) {
+            return Self { wind, wind_applicable: false, wind_aided: false, wind_legal: true, wind_missing: false };
+        }
+
+        let wind_aided = wind.is_some_and(|w| w > WIND_LIMIT);
+        Self {
+            wind,
+            wind_applicable: true,
+            wind_aided,
+            wind_legal: wind.is_some() && !wind_aided,
+            wind_missing: wind.is_none(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rounds_readings_up_to_the_tenth() {
+        assert_eq!(round_wind(2.01), 2.1);
+        assert_eq!(round_wind(2.0), 2.0);
+        assert_eq!(round_wind(1.99), 2.0);
+        assert_eq!(round_wind(-0.35), -0.3);
+    }
+
+    #[test]
+    fn a_reading_just_over_the_limit_is_aided() {
+        let status = WindStatus::new(&Category::Outdoor, "100m", Some(2.01));
+        assert_eq!(status.wind, Some(2.1));
+        assert!(status.wind_aided);
+        assert!(!status.wind_legal);
+    }
+
+    #[test]
+    fn a_reading_at_the_limit_is_legal() {
+        let status = WindStatus::new(&Category::Outdoor, "100m", Some(2.0));
+        assert_eq!(status.wind, Some(2.0));
+        assert!(!status.wind_aided);
+        assert!(status.wind_legal);
+
+        assert!(WindStatus::new(&Category::Outdoor, "Long Jump", Some(-1.5)).wind_legal);
+    }
+
+    #[test]
+    fn a_missing_reading_is_not_legal_in_wind_assisted_events() {
+        let status = WindStatus::new(&Category::Outdoor, "200m", None);
+        assert!(status.wind_missing);
+        assert!(!status.wind_aided);
+        assert!(!status.wind_legal);
+    }
+
+    #[test]
+    fn wind_only_applies_to_outdoor_sprints_and_horizontal_jumps() {
+        assert!(wind_applies(&Category::Outdoor, "110mH"));
+        assert!(wind_applies(&Category::Outdoor, "TJ"));
+        assert!(!wind_applies(&Category::Outdoor, "400m"));
+        assert!(!wind_applies(&Category::Indoor, "60m"));
+        assert!(!wind_applies(&Category::Indoor, "LJ"));
+
+        let status = WindStatus::new(&Category::Outdoor, "400m", Some(3.5));
+        assert!(!status.wind_applicable);
+        assert!(!status.wind_aided);
+        assert!(status.wind_legal);
+    }
+}
@@ -0,0 +1,397 @@
+use std::{collections::HashSet, fmt, str::FromStr};
+
+use chrono::{Months, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::models::performance::Performance;
+
+use super::events::canonical_event;
+
+/// How World Athletics grades a competition for the world rankings, from the Olympic Games and World
+/// Championships down to category F meetings.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum CompetitionCategory {
+    /// Olympic Games and World Championships.
+    Ow,
+    /// Diamond League Final.
+    Df,
+    /// Diamond League meetings and Gold level World Indoor Tour meetings.
+    Gw,
+    A,
+    B,
+    C,
+    D,
+    E,
+    F,
+}
+
+impl fmt::Display for CompetitionCategory {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            CompetitionCategory::Ow => "OW",
+            CompetitionCategory::Df => "DF",
+            CompetitionCategory::Gw => "GW",
+            CompetitionCategory::A => "A",
+            CompetitionCategory::B => "B",
+            CompetitionCategory::C => "C",
+            CompetitionCategory::D => "D",
+            CompetitionCategory::E => "E",
+            CompetitionCategory::F => "F",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for CompetitionCategory {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_uppercase().as_str() {
+            "OW" => Ok(CompetitionCategory::Ow),
+            "DF" => Ok(CompetitionCategory::Df),
+            "GW" => Ok(CompetitionCategory::Gw),
+            "A" => Ok(CompetitionCategory::A),
+            "B" => Ok(CompetitionCategory::B),
+            "C" => Ok(CompetitionCategory::C),
+            "D" => Ok(CompetitionCategory::D),
+            "E" => Ok(CompetitionCategory::E),
+            "F" => Ok(CompetitionCategory::F),
+            _ => Err(format!("unknown competition category '{}', expected OW, DF, GW or A to F", s)),
+        }
+    }
+}
+
+impl CompetitionCategory {
+    /// The placing scores in a round, with the place the first score is for. Semi-final places are
+    /// counted across all the semi-finals, so they start at 9th, the best place that missed the final.
+    fn placing_scores(self, round: Round) -> (i32, &'static [i32]) {
+        match round {
+            Round::Final => (1, self.final_placing_scores()),
+            Round::SemiFinal => (9, self.semi_final_placing_scores()),
+            Round::Heat => (1, &[]),
+        }
+    }
+
+    fn final_placing_scores(self) -> &'static [i32] {
+        match self {
+            CompetitionCategory::Ow => &[375, 330, 300, 270, 250, 230, 215, 200],
+            CompetitionCategory::Df => &[240, 210, 185, 170, 155, 145, 135, 125],
+            CompetitionCategory::Gw => &[200, 170, 150, 140, 130, 120, 110, 100],
+            CompetitionCategory::A => &[140, 120, 110, 100, 90, 80, 70, 60],
+            CompetitionCategory::B => &[100, 80, 70, 60, 55, 50, 45, 40],
+            CompetitionCategory::C => &[60, 50, 45, 40, 35, 30, 25, 20],
+            CompetitionCategory::D => &[40, 35, 30, 25, 20, 15, 10, 5],
+            CompetitionCategory::E => &[25, 20, 15, 10, 5],
+            CompetitionCategory::F => &[15, 10, 5],
+        }
+    }
+
+    /// Only the championships and the Diamond League Final score semi-final places.
+    fn semi_final_placing_scores(self) -> &'static [i32] {
+        match self {
+            CompetitionCategory::Ow => &[170, 150, 140, 130, 120, 110, 100, 90],
+            CompetitionCategory::Df => &[110, 100, 90, 80, 75, 70, 65, 60],
+            _ => &[],
+        }
+    }
+}
+
+/// The round of a competition a place was earned in.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Round {
+    Final,
+    SemiFinal,
+    /// Heats, quarter-finals and any other round, which earn no placing score.
+    Heat,
+}
+
+impl Round {
+    /// Works out the round from its name, such as `final`, `semi-final 2` or `SF1`. Performances
+    /// without a round are taken to be straight finals, as in most field events.
+    pub fn from_name(round: Option<&str>) -> Round {
+        let name: String = round.unwrap_or_default().to_lowercase().chars().filter(|c| c.is_alphanumeric()).collect();
+        match name.as_str() {
+            "" | "final" | "f" | "afinal" | "finala" => Round::Final,
+            _ if name.starts_with("semi") => Round::SemiFinal,
+            _ if name.starts_with("sf") && name[2..].chars().all(|c| c.is_ascii_digit()) => Round::SemiFinal,
+            _ => Round::Heat,
+        }
+    }
+}
+
+/// The placing score for a place in a round of a competition. Finals score the top places, and
+/// semi-finals at the highest levels score the places just outside the final.
+pub fn placing_score(category: Option<CompetitionCategory>, place: Option<i32>, round: Option<&str>) -> i32 {
+    let (Some(category), Some(place)) = (category, place) else {
+        return 0;
+    };
+    let (first_place, scores) = category.placing_scores(Round::from_name(round));
+    if place < first_place {
+        return 0;
+    }
+    scores.get((place - first_place) as usize).copied().unwrap_or(0)
+}
+
+/// How many results count towards a ranking average in an event, and how many months back they can
+/// come from. Events that are contested less often count fewer results over a longer window.
+pub fn ranking_rules(event: &str) -> (usize, u32) {
+    match canonical_event(event) {
+        Some("Marathon" | "35kmW" | "50kmW" | "100km" | "10000m") => (2, 18),
+        Some("Pentathlon" | "Heptathlon" | "Decathlon") => (3, 18),
+        Some("5km" | "10km" | "15km" | "20km" | "HM" | "10kmW" | "20kmW" | "20000mW") => (3, 18),
+        _ => (5, 12),
+    }
+}
+
+/// A performance counted towards a ranking average.
+#[derive(Debug, Clone, Serialize)]
+pub struct RankingResult {
+    pub performance_id: i32,
+    pub date: NaiveDate,
+    pub competition: Option<String>,
+    pub competition_category: Option<CompetitionCategory>,
+    pub place: Option<i32>,
+    pub round: Option<String>,
+    pub mark_display: String,
+    pub result_score: i32,
+    pub placing_score: i32,
+    pub ranking_score: i32,
+}
+
+/// An athlete's ranking average in one event.
+#[derive(Debug, Clone, Serialize)]
+pub struct EventRanking {
+    pub category: String,
+    pub gender: String,
+    /// The canonical name of the event, see [`crate::scoring::events`].
+    pub event: String,
+    /// The first and last day results are counted from.
+    pub window_start: NaiveDate,
+    pub window_end: NaiveDate,
+    pub results_required: usize,
+    /// The best results in the window, highest ranking score first.
+    pub results: Vec<RankingResult>,
+    /// The mean ranking score of the counted results.
+    pub ranking_average: f64,
+    /// The athlete has the number of results the event requires. Averages over fewer results are
+    /// provisional.
+    pub qualified: bool,
+}
+
+/// Works out an athlete's ranking average in each event from their best results in the ranking
+/// window ending on `date`.
+///
+/// Wind-aided marks, marks below the scoring tables and performances without a date do not count.
+/// `results` and `months` override the number of results and the window length the event uses.
+pub fn ranking_averages(
+    performances: &[Performance],
+    date: NaiveDate,
+    results: Option<usize>,
+    months: Option<u32>,
+) -> Vec<EventRanking> {
+    let mut rankings: Vec<EventRanking> = vec![];
+    let mut seen = HashSet::new();
+    for performance in performances {
+        let key = (&performance.category, &performance.gender, &performance.event);
+        if !seen.insert(key) {
+            continue;
+        }
+
+        let (default_results, default_months) = ranking_rules(&performance.event);
+        let (results_required, months) = (results.unwrap_or(default_results), months.unwrap_or(default_months));
+        let window_start = date
+            .checked_sub_months(Months::new(months))
+            .and_then(|start| start.succ_opt())
+            .unwrap_or(NaiveDate::MIN);
+
+        let mut counted: Vec<RankingResult> = performances
+            .iter()
+            .filter(|p| (&p.category, &p.gender, &p.event) == key && !p.wind.wind_aided)
+            .filter_map(|p| {
+                let day = p.date.filter(|d| (window_start..=date).contains(d))?;
+                Some(RankingResult {
+                    performance_id: p.id,
+                    date: day,
+                    competition: p.competition.clone(),
+                    competition_category: p.competition_category,
+                    place: p.place,
+                    round: p.round.clone(),
+                    mark_display: p.mark_display.clone(),
+                    result_score: p.points?,
+                    placing_score: p.placing_score,
+                    ranking_score: p.ranking_score?,
+                })
+            })
+            .collect();
+        counted.sort_by(|a, b| b.ranking_score.cmp(&a.ranking_score).then(a.date.cmp(&b.date)));
+        counted.truncate(results_required);
+        if counted.is_empty() {
+            continue;
+        }
+
+        rankings.push(EventRanking {
+            category: performance.category.clone(),
+            gender: performance.gender.clone(),
+            event: performance.event.clone(),
+            window_start,
+            window_end: date,
+            results_required,
+            ranking_average: average(&counted),
+            qualified: counted.len() >= results_required,
+            results: counted,
+        });
+    }
+
+    rankings.sort_by(|a, b| (&a.category, &a.gender, &a.event).cmp(&(&b.category, &b.gender, &b.event)));
+    rankings
+}
+
+/// The mean ranking score of some results, to two decimal places.
+fn average(results: &[RankingResult]) -> f64 {
+    let mean = results.iter().map(|r| r.ranking_score as f64).sum::<f64>() / results.len() as f64;
+    (mean * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDateTime;
+
+    use super::*;
+    use crate::{
+        models::iaaf_points::Category,
+        scoring::{timing::TimingMethod, track::TrackType, wind::WindStatus},
+    };
+    use CompetitionCategory::{Df, Gw, Ow, A, F};
+
+    fn day(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn performance(id: i32, event: &str, date: Option<NaiveDate>, points: Option<i32>, placing_score: i32) -> Performance {
+        Performance {
+            id,
+            user_id: 1,
+            category: "Outdoor".to_string(),
+            gender: "Male".to_string(),
+            event: event.to_string(),
+            mark: 10.0,
+            mark_display: "10.00".to_string(),
+            mark_imperial: None,
+            date,
+            competition: None,
+            venue: None,
+            competition_category: None,
+            round: None,
+            place: None,
+            wind: WindStatus::new(&Category::Outdoor, event, Some(0.0)),
+            timing: TimingMethod::default(),
+            track: TrackType::default(),
+            notes: None,
+            edition: None,
+            scored_mark: Some(10.0),
+            scored_mark_display: Some("10.00".to_string()),
+            points,
+            placing_score,
+            ranking_score: points.map(|p| p + placing_score),
+            lower_is_better: true,
+            date_created: NaiveDateTime::default(),
+            date_modified: None,
+        }
+    }
+
+    #[test]
+    fn finals_score_the_top_places() {
+        assert_eq!(placing_score(Some(Ow), Some(1), Some("Final")), 375);
+        assert_eq!(placing_score(Some(Ow), Some(8), None), 200);
+        assert_eq!(placing_score(Some(Ow), Some(9), Some("final")), 0);
+        assert_eq!(placing_score(Some(Gw), Some(2), Some("")), 170);
+        assert_eq!(placing_score(Some(F), Some(3), None), 5);
+        assert_eq!(placing_score(Some(F), Some(4), None), 0);
+    }
+
+    #[test]
+    fn championship_semi_finals_score_the_places_outside_the_final() {
+        assert_eq!(placing_score(Some(Ow), Some(9), Some("Semi-final 2")), 170);
+        assert_eq!(placing_score(Some(Ow), Some(16), Some("SF1")), 90);
+        assert_eq!(placing_score(Some(Df), Some(9), Some("semi")), 110);
+        // The top eight go through and score in the final instead.
+        assert_eq!(placing_score(Some(Ow), Some(8), Some("semi-final")), 0);
+        assert_eq!(placing_score(Some(Ow), Some(17), Some("semi-final")), 0);
+        assert_eq!(placing_score(Some(A), Some(9), Some("semi-final")), 0);
+    }
+
+    #[test]
+    fn other_rounds_and_missing_places_score_nothing() {
+        assert_eq!(placing_score(Some(Ow), Some(1), Some("heat 3")), 0);
+        assert_eq!(placing_score(Some(Ow), Some(1), Some("QF")), 0);
+        assert_eq!(placing_score(Some(Ow), Some(0), None), 0);
+        assert_eq!(placing_score(Some(Ow), Some(-1), Some("semi-final")), 0);
+        assert_eq!(placing_score(Some(Ow), None, None), 0);
+        assert_eq!(placing_score(None, Some(1), None), 0);
+    }
+
+    #[test]
+    fn names_rounds() {
+        assert_eq!(Round::from_name(None), Round::Final);
+        assert_eq!(Round::from_name(Some(" Final A ")), Round::Final);
+        assert_eq!(Round::from_name(Some("Semi Final")), Round::SemiFinal);
+        assert_eq!(Round::from_name(Some("sf")), Round::SemiFinal);
+        assert_eq!(Round::from_name(Some("SF3")), Round::SemiFinal);
+        assert_eq!(Round::from_name(Some("sfx")), Round::Heat);
+        assert_eq!(Round::from_name(Some("B Final")), Round::Heat);
+        assert_eq!(Round::from_name(Some("Heat 1")), Round::Heat);
+    }
+
+    #[test]
+    fn averages_the_best_results_in_the_window() {
+        let today = day(2026, 6, 30);
+        let performances = [
+            performance(1, "100m", Some(day(2026, 6, 1)), Some(1100), 100),
+            performance(2, "100m", Some(day(2026, 5, 1)), Some(1150), 0),
+            performance(3, "100m", Some(day(2026, 4, 1)), Some(1000), 5),
+            performance(4, "100m", Some(day(2026, 3, 1)), Some(900), 0),
+            // Outside the twelve month window, below the table or undated.
+            performance(5, "100m", Some(day(2025, 6, 30)), Some(1300), 0),
+            performance(6, "100m", Some(day(2026, 2, 1)), None, 0),
+            performance(7, "100m", None, Some(1300), 0),
+        ];
+
+        let rankings = ranking_averages(&performances, today, Some(3), None);
+        assert_eq!(rankings.len(), 1);
+        let ranking = &rankings[0];
+        assert_eq!(ranking.window_start, day(2025, 7, 1));
+        assert_eq!(ranking.results.iter().map(|r| r.performance_id).collect::<Vec<_>>(), [1, 2, 3]);
+        assert_eq!(ranking.ranking_average, 1118.33);
+        assert!(ranking.qualified);
+    }
+
+    #[test]
+    fn averages_over_fewer_results_are_provisional() {
+        let performances = [
+            performance(1, "Marathon", Some(day(2025, 3, 1)), Some(1200), 0),
+            performance(2, "100m", Some(day(2026, 6, 1)), Some(1000), 0),
+        ];
+
+        let rankings = ranking_averages(&performances, day(2026, 6, 30), None, None);
+        let events: Vec<&str> = rankings.iter().map(|r| r.event.as_str()).collect();
+        assert_eq!(events, ["100m", "Marathon"]);
+        assert_eq!(rankings[0].results_required, 5);
+        assert!(!rankings[0].qualified);
+        // The marathon counts two results over eighteen months.
+        assert_eq!(rankings[1].results_required, 2);
+        assert_eq!(rankings[1].ranking_average, 1200.0);
+        assert!(!rankings[1].qualified);
+    }
+
+    #[test]
+    fn wind_aided_results_do_not_count() {
+        let mut aided = performance(1, "100m", Some(day(2026, 6, 1)), Some(1200), 0);
+        aided.wind = WindStatus::new(&Category::Outdoor, "100m", Some(2.1));
+        let performances = [aided, performance(2, "100m", Some(day(2026, 6, 2)), Some(1000), 0)];
+
+        let rankings = ranking_averages(&performances, day(2026, 6, 30), Some(1), None);
+        assert_eq!(rankings[0].results[0].performance_id, 2);
+    }
+}